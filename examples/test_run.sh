@@ -4,6 +4,12 @@ make || exit 1
 
 declare format='directory'
 #declare format='tarball'
+#declare format='squashfs'
+#declare format='ext4'
+#declare format='img'
+#declare format='oci'
+#declare format='oci-archive'
+#declare format='cpio'
 
 declare -a mirrors=(
   # Debian mirrors:
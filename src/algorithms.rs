@@ -1,48 +1,20 @@
+use crate::database::*;
 use crate::functions::*;
 use crate::package::*;
+use crate::solver::*;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::Path;
 
 pub fn resolve_dependencies(
-    package_database: &HashMap<String, Vec<Package>>,
+    package_database: &PackageDatabase,
     input_package_set: &Vec<Package>,
     consider_recommends: &bool,
     packages_to_prohibit: &Vec<String>,
+    provider_preferences: &HashMap<String, String>,
+    architecture_config: &ArchitectureConfig,
     message_config: &MessageConfig,
 ) -> Result<Vec<Package>, ()> {
-    #[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
-    struct Provider {
-        package: Package,
-        provides: Vec<String>,
-    }
-
-    let mut provides_list: Vec<Provider> = Vec::new();
-
-    for package in package_database.values() {
-        if package[0].provides.len() != 0 {
-            let mut provides: Vec<String> = Vec::new();
-
-            for provided in &package[0].provides {
-                provides.push(provided[0].name.clone());
-            }
-
-            let provider: Provider = Provider {
-                package: package[0].clone(),
-                provides: provides,
-            };
-
-            provides_list.push(provider);
-        };
-    }
-
-    provides_list.sort_unstable();
-    provides_list.dedup();
-
-    let provides_list: Vec<Provider> = provides_list;
-
-    //////////////////////////////////////////////
-
     let mut current_packages: Vec<Package> = input_package_set.clone();
 
     let mut accumulated_packages: Vec<Package> = Vec::new();
@@ -93,118 +65,187 @@ pub fn resolve_dependencies(
         let mut dependency_packages: Vec<Package> = Vec::new();
 
         for current in current_packages {
-            let mut packages_not_found: Vec<String> = Vec::new();
+            let mut relationships_to_satisfy: Vec<Vec<Relationship>> = current.pre_depends.clone();
 
-            for dependency in current.depends {
-                let mut was_dependency_found: bool = false;
+            relationships_to_satisfy.extend(current.depends.clone());
 
-                for alternative in &dependency {
-                    match package_database.get(&alternative.name) {
-                        Some(result) => {
-                            dependency_packages.push(result[0].clone());
-                            was_dependency_found = true;
-                            break;
-                        }
-                        None => {}
-                    };
-                }
+            if *consider_recommends == true {
+                relationships_to_satisfy.extend(current.recommends.clone());
+            };
 
-                if was_dependency_found == false {
-                    packages_not_found.push(dependency[0].name.clone());
-                };
-            }
+            let mut unsatisfied_dependencies: Vec<(Vec<Relationship>, Vec<String>)> = Vec::new();
 
-            for dependency in current.pre_depends {
+            for dependency in relationships_to_satisfy {
                 let mut was_dependency_found: bool = false;
+                let mut conflict_explanations: Vec<String> = Vec::new();
 
                 for alternative in &dependency {
-                    match package_database.get(&alternative.name) {
-                        Some(result) => {
-                            dependency_packages.push(result[0].clone());
-                            was_dependency_found = true;
-                            break;
-                        }
-                        None => {}
+                    // a package that is already selected is kept as long as its version satisfies
+                    // the relation, so the same package isn't selected in two versions.
+                    let is_selected_and_satisfying: bool = accumulated_packages
+                        .iter()
+                        .chain(dependency_packages.iter())
+                        .any(|selected| {
+                            does_package_satisfy(
+                                selected,
+                                alternative,
+                                &current.architecture,
+                                &architecture_config,
+                            )
+                        });
+
+                    if is_selected_and_satisfying == true {
+                        was_dependency_found = true;
+                        break;
                     };
-                }
 
-                if was_dependency_found == false {
-                    packages_not_found.push(dependency[0].name.clone());
-                };
-            }
-
-            if *consider_recommends == true {
-                for dependency in current.recommends {
-                    let mut was_dependency_found: bool = false;
+                    // candidates that conflict with an already selected package are skipped, so
+                    // a later alternative (or an older version) can be chosen instead.
+                    for candidate in select_candidates(
+                        &package_database,
+                        &alternative,
+                        &current.architecture,
+                        &architecture_config,
+                    ) {
+                        match find_conflict_with_selection(
+                            &candidate,
+                            &accumulated_packages,
+                            &dependency_packages,
+                            &architecture_config,
+                        ) {
+                            Some(explanation) => {
+                                print_message(
+                                    "debug",
+                                    &format!("skipping candidate: {explanation}"),
+                                    &message_config,
+                                );
 
-                    for alternative in &dependency {
-                        match package_database.get(&alternative.name) {
-                            Some(result) => {
-                                dependency_packages.push(result[0].clone());
+                                conflict_explanations.push(explanation);
+                            }
+                            None => {
+                                dependency_packages.push(candidate);
                                 was_dependency_found = true;
                                 break;
                             }
-                            None => {}
                         };
                     }
 
-                    if was_dependency_found == false {
-                        packages_not_found.push(dependency[0].name.clone());
+                    if was_dependency_found == true {
+                        break;
                     };
                 }
-            };
 
-            for package in packages_not_found {
+                if was_dependency_found == false {
+                    unsatisfied_dependencies.push((dependency, conflict_explanations));
+                };
+            }
+
+            for (dependency, mut conflict_explanations) in unsatisfied_dependencies {
                 let mut was_package_found: bool = false;
 
-                for provider in &provides_list {
-                    if provider.provides.contains(&package) == true {
-                        if accumulated_packages.contains(&provider.package) {
-                            print_message(
-                                "debug",
-                                &format!(
-                                    "package: \"{}\" is provided by: \"{}\"",
-                                    package, provider.package.name,
-                                ),
-                                &message_config,
-                            );
+                for alternative in &dependency {
+                    for provider in select_providers(
+                        &package_database,
+                        &alternative,
+                        &current.architecture,
+                        &[&accumulated_packages, &dependency_packages],
+                        &provider_preferences,
+                        &architecture_config,
+                    ) {
+                        let is_selected: bool = accumulated_packages.contains(&provider)
+                            || dependency_packages.contains(&provider);
+
+                        if is_selected == false {
+                            match find_conflict_with_selection(
+                                &provider,
+                                &accumulated_packages,
+                                &dependency_packages,
+                                &architecture_config,
+                            ) {
+                                Some(explanation) => {
+                                    conflict_explanations.push(explanation);
+                                    continue;
+                                }
+                                None => dependency_packages.push(provider.clone()),
+                            };
+                        };
 
-                            was_package_found = true;
+                        print_message(
+                            "debug",
+                            &format!(
+                                "package: \"{}\" is provided by: \"{}\"",
+                                alternative.name, provider.name,
+                            ),
+                            &message_config,
+                        );
 
-                            break;
-                        };
+                        was_package_found = true;
+
+                        break;
+                    }
+
+                    if was_package_found == true {
+                        break;
                     };
                 }
 
                 if was_package_found == false {
-                    for provider in &provides_list {
-                        if provider.provides.contains(&package) == true {
-                            if accumulated_packages.contains(&provider.package) == false {
-                                dependency_packages.push(provider.package.clone());
+                    let mut available_versions: Vec<String> = Vec::new();
 
-                                print_message(
-                                    "debug",
-                                    &format!(
-                                        "package: \"{}\" is provided by: \"{}\"",
-                                        package, provider.package.name,
-                                    ),
-                                    &message_config,
-                                );
+                    for alternative in &dependency {
+                        for package in find_packages_by_name(
+                            &package_database,
+                            &alternative.name,
+                            &architecture_config,
+                        ) {
+                            available_versions.push(format!(
+                                "{} ({})",
+                                format_package_name(package, &architecture_config),
+                                package.version
+                            ));
+                        }
+                    }
 
-                                was_package_found = true;
+                    if conflict_explanations.len() != 0 {
+                        print_message(
+                            "error",
+                            &format!(
+                                "every candidate for: \"{}\" required by: \"{}\" ({}) conflicts with the selected packages:",
+                                format_relationship_alternatives(&dependency),
+                                current.name,
+                                current.version,
+                            ),
+                            &message_config,
+                        );
 
-                                break;
-                            };
-                        };
-                    }
-                };
+                        for explanation in &conflict_explanations {
+                            print_message("error", &format!("    {explanation}"), &message_config);
+                        }
+                    } else if available_versions.len() == 0 {
+                        print_message(
+                            "error",
+                            &format!(
+                                "failed to find package: \"{}\" required by: \"{}\" ({})",
+                                format_relationship_alternatives(&dependency),
+                                current.name,
+                                current.version,
+                            ),
+                            &message_config,
+                        );
+                    } else {
+                        print_message(
+                            "error",
+                            &format!(
+                                "no available version satisfies: \"{}\" required by: \"{}\" ({}), available: \"{}\"",
+                                format_relationship_alternatives(&dependency),
+                                current.name,
+                                current.version,
+                                available_versions.join(", "),
+                            ),
+                            &message_config,
+                        );
+                    };
 
-                if was_package_found == false {
-                    print_message(
-                        "error",
-                        &format!("failed to find package: \"{package}\""),
-                        &message_config,
-                    );
                     return Err(());
                 };
             }
@@ -227,22 +268,682 @@ pub fn resolve_dependencies(
         return Err(());
     };
 
+    let mut conflicting_packages: Vec<String> = Vec::new();
+
+    for (index, package) in accumulated_packages.iter().enumerate() {
+        if index != 0
+            && accumulated_packages[index - 1].name == package.name
+            && accumulated_packages[index - 1].architecture == package.architecture
+        {
+            conflicting_packages.push(format!(
+                "{} ({} and {})",
+                package.name,
+                accumulated_packages[index - 1].version,
+                package.version
+            ));
+        };
+    }
+
+    if conflicting_packages.len() != 0 {
+        for conflicting in &conflicting_packages {
+            print_message(
+                "error",
+                &format!("package was selected in conflicting versions: \"{conflicting}\""),
+                &message_config,
+            );
+        }
+        return Err(());
+    };
+
+    let conflict_explanations: Vec<String> =
+        find_conflicts(&accumulated_packages, &architecture_config);
+
+    if conflict_explanations.len() != 0 {
+        print_message(
+            "error",
+            "the requested package set is uninstallable:",
+            &message_config,
+        );
+
+        for explanation in &conflict_explanations {
+            print_message("error", &format!("    {explanation}"), &message_config);
+        }
+        return Err(());
+    };
+
     return Ok(accumulated_packages);
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+pub fn resolve_dependencies_with(
+    target_resolver: &str,
+    package_database: &PackageDatabase,
+    input_package_set: &Vec<Package>,
+    consider_recommends: &bool,
+    packages_to_prohibit: &Vec<String>,
+    provider_preferences: &HashMap<String, String>,
+    architecture_config: &ArchitectureConfig,
+    message_config: &MessageConfig,
+) -> Result<Vec<Package>, ()> {
+    // the resolver 'none' still needs the subsets of the target package set to be resolved, which
+    // has always been done by the internal resolver, the same goes for external solvers.
+    match target_resolver {
+        "sat" => {
+            return solve_dependencies(
+                package_database,
+                input_package_set,
+                consider_recommends,
+                packages_to_prohibit,
+                provider_preferences,
+                architecture_config,
+                message_config,
+            );
+        }
+        _ => {
+            return resolve_dependencies(
+                package_database,
+                input_package_set,
+                consider_recommends,
+                packages_to_prohibit,
+                provider_preferences,
+                architecture_config,
+                message_config,
+            );
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn normalize_architecture<'a>(
+    architecture: &'a str,
+    architecture_config: &'a ArchitectureConfig,
+) -> &'a str {
+    // packages of architecture 'all' behave like packages of the primary architecture.
+    if architecture == "all" {
+        return &architecture_config.primary_architecture;
+    };
+
+    return architecture;
+}
+
+pub fn format_package_name(package: &Package, architecture_config: &ArchitectureConfig) -> String {
+    if normalize_architecture(&package.architecture, architecture_config)
+        == architecture_config.primary_architecture
+    {
+        return package.name.clone();
+    };
+
+    return format!("{}:{}", package.name, package.architecture);
+}
+
+pub fn does_architecture_satisfy(
+    package: &Package,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    architecture_config: &ArchitectureConfig,
+) -> bool {
+    let package_architecture: &str =
+        normalize_architecture(&package.architecture, architecture_config);
+    let dependent_architecture: &str =
+        normalize_architecture(dependent_architecture, architecture_config);
+
+    // these are the rules of dpkg, an unqualified relation is satisfied by a package of the same
+    // architecture or a 'Multi-Arch: foreign' one, ':any' also by a 'Multi-Arch: allowed' one.
+    match &relationship.architecture as &str {
+        "" => {
+            return package_architecture == dependent_architecture
+                || package.multi_arch == "foreign";
+        }
+        "any" => {
+            return package_architecture == dependent_architecture
+                || package.multi_arch == "foreign"
+                || package.multi_arch == "allowed";
+        }
+        "native" => return package_architecture == architecture_config.primary_architecture,
+        architecture => return package_architecture == architecture,
+    };
+}
+
+pub fn does_package_satisfy(
+    package: &Package,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    architecture_config: &ArchitectureConfig,
+) -> bool {
+    return *package.name == *relationship.name
+        && does_version_satisfy(&package.version, &relationship.version) == true
+        && does_architecture_satisfy(
+            package,
+            relationship,
+            dependent_architecture,
+            architecture_config,
+        ) == true;
+}
+
+pub fn find_packages_by_name<'a>(
+    package_database: &'a PackageDatabase,
+    package_name: &str,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<&'a Package> {
+    let mut output: Vec<&Package> = Vec::new();
+
+    for architecture in architecture_config
+        .list_of_architectures
+        .iter()
+        .map(|architecture| architecture as &str)
+        .chain(["all"])
+    {
+        output.extend(package_database.get(package_name, architecture).iter());
+    }
+
+    return output;
+}
+
+pub fn find_packages_by_qualified_name<'a>(
+    package_database: &'a PackageDatabase,
+    qualified_name: &str,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<&'a Package> {
+    let mut output: Vec<&Package> = Vec::new();
+
+    // 'name:architecture' selects a foreign package, a plain name a native one.
+    let (name, architectures) = match qualified_name.split_once(':') {
+        Some((name, "native")) => (
+            name,
+            Vec::from([&architecture_config.primary_architecture as &str]),
+        ),
+        Some((name, architecture)) => (name, Vec::from([architecture])),
+        None => (
+            qualified_name,
+            Vec::from([&architecture_config.primary_architecture as &str, "all"]),
+        ),
+    };
+
+    for architecture in architectures {
+        output.extend(package_database.get(name, architecture).iter());
+    }
+
+    return output;
+}
+
+pub fn find_packages_by_specification<'a>(
+    package_database: &'a PackageDatabase,
+    specification: &str,
+    architecture_config: &ArchitectureConfig,
+    message_config: &MessageConfig,
+) -> Result<Vec<&'a Package>, ()> {
+    let mut qualified_name: &str = specification;
+    let mut version: &str = "";
+    let mut suite: &str = "";
+
+    // like apt, 'name=version' selects a version and 'name/suite' the suite it is taken from.
+    match specification.split_once('=') {
+        Some((name, selected_version)) => {
+            qualified_name = name;
+            version = selected_version;
+        }
+        None => match specification.split_once('/') {
+            Some((name, selected_suite)) => {
+                qualified_name = name;
+                suite = selected_suite;
+            }
+            None => {}
+        },
+    };
+
+    let mut candidates: Vec<&Package> =
+        find_packages_by_qualified_name(package_database, qualified_name, architecture_config);
+
+    if candidates.len() == 0 {
+        print_message(
+            "error",
+            &format!("failed to find package: \"{qualified_name}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    // the highest pin priority comes first, then the highest version, equal versions keep the
+    // order of the indices they were read from.
+    candidates.sort_by(|a, b| {
+        return b
+            .pin_priority
+            .cmp(&a.pin_priority)
+            .then(compare_versions(&b.version, &a.version));
+    });
+
+    // a negative pin priority prevents a package from being selected, unless the version or the
+    // suite is selected explicitly.
+    if version.is_empty() == true && suite.is_empty() == true {
+        candidates.retain(|package| package.pin_priority >= 0);
+
+        if candidates.len() == 0 {
+            print_message(
+                "error",
+                &format!(
+                    "every version of package: \"{qualified_name}\" has a negative pin priority"
+                ),
+                &message_config,
+            );
+            return Err(());
+        };
+    };
+
+    if version.is_empty() == false {
+        let available_versions: Vec<String> = candidates
+            .iter()
+            .map(|package| package.version.clone())
+            .collect::<Vec<String>>();
+
+        candidates.retain(|package| package.version == version);
+
+        if candidates.len() == 0 {
+            print_message(
+                "error",
+                &format!(
+                    "version: \"{version}\" of package: \"{qualified_name}\" is not available, available: \"{}\"",
+                    available_versions.join(", ")
+                ),
+                &message_config,
+            );
+            return Err(());
+        };
+    };
+
+    if suite.is_empty() == false {
+        let mut available_suites: Vec<String> = candidates
+            .iter()
+            .map(|package| package.origin_suite.clone())
+            .collect::<Vec<String>>();
+
+        available_suites.sort_unstable();
+        available_suites.dedup();
+
+        candidates.retain(|package| package.origin_suite == suite);
+
+        if candidates.len() == 0 {
+            print_message(
+                "error",
+                &format!(
+                    "package: \"{qualified_name}\" is not available in suite: \"{suite}\", available in: \"{}\"",
+                    available_suites.join(", ")
+                ),
+                &message_config,
+            );
+            return Err(());
+        };
+    };
+
+    return Ok(candidates);
+}
+
+pub fn select_candidates(
+    package_database: &PackageDatabase,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<Package> {
+    let mut candidates: Vec<Package> =
+        find_packages_by_name(package_database, &relationship.name, architecture_config)
+            .into_iter()
+            .filter(|package| {
+                package.pin_priority >= 0
+                    && does_package_satisfy(
+                        package,
+                        relationship,
+                        dependent_architecture,
+                        architecture_config,
+                    ) == true
+            })
+            .map(|package| package.clone())
+            .collect::<Vec<Package>>();
+
+    let dependent_architecture: &str =
+        normalize_architecture(dependent_architecture, architecture_config);
+
+    // packages of the dependent's architecture come first, then the highest pin priority and the
+    // highest satisfying version, equal versions keep the order of the indices they were read from.
+    candidates.sort_by(|a, b| {
+        return (normalize_architecture(&b.architecture, architecture_config)
+            == dependent_architecture)
+            .cmp(
+                &(normalize_architecture(&a.architecture, architecture_config)
+                    == dependent_architecture),
+            )
+            .then(b.pin_priority.cmp(&a.pin_priority))
+            .then(compare_versions(&b.version, &a.version));
+    });
+
+    return candidates;
+}
+
+pub fn select_candidate(
+    package_database: &PackageDatabase,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    architecture_config: &ArchitectureConfig,
+) -> Option<Package> {
+    return select_candidates(
+        package_database,
+        relationship,
+        dependent_architecture,
+        architecture_config,
+    )
+    .into_iter()
+    .next();
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn rank_priority(priority: &str) -> u8 {
+    match priority {
+        "required" => return 0,
+        "important" => return 1,
+        "standard" => return 2,
+        "optional" => return 3,
+        "extra" => return 4,
+        _ => return 5,
+    };
+}
+
+pub fn does_package_provide(package: &Package, relationship: &Relationship) -> bool {
+    for provided in package.provides.iter().flatten() {
+        if provided.name != relationship.name {
+            continue;
+        };
+
+        if relationship.version.is_empty() == true {
+            return true;
+        };
+
+        // only a versioned Provides ('foo (= 1.0)') can satisfy a versioned relation.
+        if provided.version.starts_with("=") == true
+            && does_version_satisfy(
+                provided.version.trim_start_matches('=').trim(),
+                &relationship.version,
+            ) == true
+        {
+            return true;
+        };
+    }
+
+    return false;
+}
+
+pub fn select_providers(
+    package_database: &PackageDatabase,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    selected_packages: &[&Vec<Package>],
+    provider_preferences: &HashMap<String, String>,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<Package> {
+    let mut providers: Vec<Package> = package_database
+        .get_providers(&relationship.name)
+        .into_iter()
+        .filter(|package| {
+            package.pin_priority >= 0
+                && does_package_provide(package, relationship) == true
+                && does_architecture_satisfy(
+                    package,
+                    relationship,
+                    dependent_architecture,
+                    architecture_config,
+                ) == true
+        })
+        .map(|package| package.clone())
+        .collect::<Vec<Package>>();
+
+    let preferred_provider: Option<&String> = provider_preferences.get(&*relationship.name);
+
+    let is_selected = |package: &Package| -> bool {
+        return selected_packages
+            .iter()
+            .any(|selection| selection.contains(package) == true);
+    };
+
+    // providers are ordered by the preference of the user, then by whether they are already
+    // selected, their priority, whether they are essential, and finally by name, pin priority, and
+    // version.
+    providers.sort_by(|a, b| {
+        return (preferred_provider == Some(&b.name))
+            .cmp(&(preferred_provider == Some(&a.name)))
+            .then(is_selected(b).cmp(&is_selected(a)))
+            .then(rank_priority(&a.priority).cmp(&rank_priority(&b.priority)))
+            .then(b.is_essential.cmp(&a.is_essential))
+            .then(a.name.cmp(&b.name))
+            .then(b.pin_priority.cmp(&a.pin_priority))
+            .then(compare_versions(&b.version, &a.version));
+    });
+
+    return providers;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn find_conflict(
+    package: &Package,
+    other: &Package,
+    architecture_config: &ArchitectureConfig,
+) -> Option<String> {
+    // a package can only be installed once per architecture, and for several architectures only if
+    // it is 'Multi-Arch: same' in the same version.
+    if package.name == other.name {
+        if normalize_architecture(&package.architecture, architecture_config)
+            == normalize_architecture(&other.architecture, architecture_config)
+        {
+            if package.version == other.version {
+                return None;
+            };
+
+            return Some(format!(
+                "only one version of: \"{}\" can be installed ({} or {})",
+                package.name, package.version, other.version
+            ));
+        };
+
+        if package.multi_arch == "same"
+            && other.multi_arch == "same"
+            && package.version == other.version
+        {
+            return None;
+        };
+
+        return Some(format!(
+            "\"{}\" can't be installed for both: \"{}\" ({}) and \"{}\" ({})",
+            package.name, package.architecture, package.version, other.architecture, other.version,
+        ));
+    };
+
+    for (field, relationships) in [
+        ("conflicts with", &package.conflicts),
+        ("breaks", &package.breaks),
+    ] {
+        for relationship in relationships.iter().flatten() {
+            // an unqualified relation applies to every architecture.
+            if relationship.architecture.is_empty() == false
+                && does_architecture_satisfy(
+                    other,
+                    relationship,
+                    &package.architecture,
+                    architecture_config,
+                ) == false
+            {
+                continue;
+            };
+
+            if *relationship.name == *other.name
+                && does_version_satisfy(&other.version, &relationship.version) == true
+            {
+                return Some(format!(
+                    "\"{}\" ({}) {field}: \"{}\", selected: \"{}\" ({})",
+                    package.name,
+                    package.version,
+                    format_relationship_alternatives(&Vec::from([relationship.clone()])),
+                    other.name,
+                    other.version,
+                ));
+            };
+
+            // the relation also applies to every package providing that name, a versioned one only
+            // to a matching versioned Provides.
+            if does_package_provide(other, relationship) == true {
+                return Some(format!(
+                    "\"{}\" ({}) {field}: \"{}\", provided by selected: \"{}\" ({})",
+                    package.name, package.version, relationship.name, other.name, other.version,
+                ));
+            };
+        }
+    }
+
+    return None;
+}
+
+pub fn find_conflict_with_selection(
+    candidate: &Package,
+    accumulated_packages: &Vec<Package>,
+    pending_packages: &Vec<Package>,
+    architecture_config: &ArchitectureConfig,
+) -> Option<String> {
+    for selected in accumulated_packages.iter().chain(pending_packages.iter()) {
+        match find_conflict(candidate, selected, architecture_config) {
+            Some(explanation) => return Some(explanation),
+            None => {}
+        };
+
+        match find_conflict(selected, candidate, architecture_config) {
+            Some(explanation) => return Some(explanation),
+            None => {}
+        };
+    }
+
+    return None;
+}
+
+pub fn find_conflicts(
+    package_set: &Vec<Package>,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<String> {
+    let mut package_indices: HashMap<&str, Vec<usize>> = HashMap::new();
+
+    for (index, package) in package_set.iter().enumerate() {
+        package_indices
+            .entry(&package.name)
+            .or_default()
+            .push(index);
+
+        for provided in package.provides.iter().flatten() {
+            package_indices
+                .entry(&provided.name)
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut explanations: Vec<String> = Vec::new();
+
+    for (package_index, package) in package_set.iter().enumerate() {
+        // the same package in several architectures or versions.
+        for index in &package_indices[&package.name as &str] {
+            if *index > package_index && package_set[*index].name == package.name {
+                match find_conflict(package, &package_set[*index], architecture_config) {
+                    Some(explanation) => explanations.push(explanation),
+                    None => {}
+                };
+            };
+        }
+
+        for relationship in package
+            .conflicts
+            .iter()
+            .chain(package.breaks.iter())
+            .flatten()
+        {
+            match package_indices.get(&relationship.name as &str) {
+                Some(indices) => {
+                    for index in indices {
+                        match find_conflict(package, &package_set[*index], architecture_config) {
+                            Some(explanation) => {
+                                if explanations.contains(&explanation) == false {
+                                    explanations.push(explanation);
+                                };
+                            }
+                            None => {}
+                        };
+                    }
+                }
+                None => {}
+            };
+        }
+    }
+
+    return explanations;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// everything that is needed to resolve the subsets of the target package set the same way as the
+// target package set itself.
+pub struct SubsetConfig<'a> {
+    pub package_database: &'a PackageDatabase,
+    pub target_resolver: &'a str,
+    pub packages_to_prohibit: &'a Vec<String>,
+    pub packages_to_consider_essential: &'a Vec<String>,
+    pub packages_to_consider_non_essential: &'a Vec<String>,
+    pub provider_preferences: &'a HashMap<String, String>,
+    pub architecture_config: &'a ArchitectureConfig,
+}
+
 pub fn separate_packages_by_priority(
-    package_database: &HashMap<String, Vec<Package>>,
+    subset_config: &SubsetConfig,
     input_directory: &str,
     target_package_set: &Vec<Package>,
-    packages_to_prohibit: &Vec<String>,
-    packages_to_consider_essential: &Vec<String>,
-    packages_to_consider_non_essential: &Vec<String>,
     target_extractor: &str,
     output_directory: &str,
     message_config: &MessageConfig,
 ) -> Result<(), ()> {
+    let package_database: &PackageDatabase = subset_config.package_database;
+    let target_resolver: &str = subset_config.target_resolver;
+    let packages_to_prohibit: &Vec<String> = subset_config.packages_to_prohibit;
+    let packages_to_consider_essential: &Vec<String> = subset_config.packages_to_consider_essential;
+    let packages_to_consider_non_essential: &Vec<String> =
+        subset_config.packages_to_consider_non_essential;
+    let provider_preferences: &HashMap<String, String> = subset_config.provider_preferences;
+    let architecture_config: &ArchitectureConfig = subset_config.architecture_config;
+
+    // like '--include', a plain name is a native package and 'name:architecture' a foreign one, so
+    // e.g. "libc6" and "libc6:i386" are different packages.
+    let does_package_match = |package: &Package, qualified_name: &str| -> bool {
+        let qualified_name: &str = match qualified_name.split_once(':') {
+            Some((name, "native")) | Some((name, "all")) => name,
+            Some((name, architecture))
+                if architecture == architecture_config.primary_architecture =>
+            {
+                name
+            }
+            _ => qualified_name,
+        };
+
+        return format_package_name(package, architecture_config) == qualified_name;
+    };
+
+    // the choices of an external solver can't be repeated for the subsets, so they are resolved
+    // within its result instead.
+    let mut target_package_database: PackageDatabase = PackageDatabase::default();
+
+    if target_resolver.starts_with("edsp:") == true {
+        target_package_database = PackageDatabase::new(target_package_set.clone());
+    };
+
+    let subset_package_database: &PackageDatabase;
+
+    match target_resolver.starts_with("edsp:") {
+        true => subset_package_database = &target_package_database,
+        false => subset_package_database = package_database,
+    };
+
     let mut initial_essential_subset: Vec<Package> = Vec::new();
     let mut initial_required_subset: Vec<Package> = Vec::new();
     let mut initial_important_subset: Vec<Package> = Vec::new();
@@ -328,7 +1029,7 @@ pub fn separate_packages_by_priority(
         for included in packages_to_consider_essential {
             if initial_essential_subset
                 .iter()
-                .any(|initial| &initial.name == included)
+                .any(|initial| does_package_match(initial, included) == true)
                 == true
             {
                 print_message(
@@ -339,23 +1040,27 @@ pub fn separate_packages_by_priority(
             } else {
                 let mut is_package_present: bool = false;
 
-                for package in package_database.values() {
-                    if package[0].name == *included {
-                        initial_essential_subset.push(package[0].clone());
-
-                        print_message(
-                            "debug",
-                            &format!("essential package added:   \"{included}\""),
-                            &message_config,
-                        );
-
+                match find_packages_by_qualified_name(
+                    &package_database,
+                    included,
+                    &architecture_config,
+                )
+                .first()
+                {
+                    Some(result) => {
+                        initial_essential_subset.push((*result).clone());
                         is_package_present = true;
+                    }
+                    None => {}
+                };
 
-                        break;
-                    };
-                }
-
-                if is_package_present == false {
+                if is_package_present == true {
+                    print_message(
+                        "debug",
+                        &format!("essential package added:   \"{included}\""),
+                        &message_config,
+                    );
+                } else {
                     print_message(
                         "error",
                         &format!("failed to find package: \"{included}\""),
@@ -374,22 +1079,17 @@ pub fn separate_packages_by_priority(
         for excluded in packages_to_consider_non_essential {
             if initial_essential_subset
                 .iter()
-                .any(|initial| &initial.name == excluded)
+                .any(|initial| does_package_match(initial, excluded) == true)
                 == true
             {
-                for (index, initial) in initial_essential_subset.iter_mut().enumerate() {
-                    if initial.name == *excluded {
-                        initial_essential_subset.remove(index);
-
-                        print_message(
-                            "debug",
-                            &format!("essential package removed: \"{excluded}\""),
-                            &message_config,
-                        );
+                initial_essential_subset
+                    .retain(|initial| does_package_match(initial, excluded) == false);
 
-                        break;
-                    };
-                }
+                print_message(
+                    "debug",
+                    &format!("essential package removed: \"{excluded}\""),
+                    &message_config,
+                );
             } else {
                 print_message(
                     "debug",
@@ -416,11 +1116,14 @@ pub fn separate_packages_by_priority(
 
     print_message("debug", "calculating essential subset.", &message_config);
 
-    match resolve_dependencies(
-        &package_database,
+    match resolve_dependencies_with(
+        &target_resolver,
+        &subset_package_database,
         &initial_essential_subset,
         &false,
         &packages_to_prohibit,
+        &provider_preferences,
+        &architecture_config,
         &quiet_message_config,
     ) {
         Ok(result) => target_essential_subset = result,
@@ -430,11 +1133,14 @@ pub fn separate_packages_by_priority(
     if initial_required_subset.len() != 0 {
         print_message("debug", "calculating required subset.", &message_config);
 
-        match resolve_dependencies(
-            &package_database,
+        match resolve_dependencies_with(
+            &target_resolver,
+            &subset_package_database,
             &initial_required_subset,
             &false,
             &packages_to_prohibit,
+            &provider_preferences,
+            &architecture_config,
             &quiet_message_config,
         ) {
             Ok(result) => target_required_subset = result,
@@ -445,11 +1151,14 @@ pub fn separate_packages_by_priority(
     if initial_important_subset.len() != 0 {
         print_message("debug", "calculating important subset.", &message_config);
 
-        match resolve_dependencies(
-            &package_database,
+        match resolve_dependencies_with(
+            &target_resolver,
+            &subset_package_database,
             &initial_important_subset,
             &false,
             &packages_to_prohibit,
+            &provider_preferences,
+            &architecture_config,
             &quiet_message_config,
         ) {
             Ok(result) => target_important_subset = result,
@@ -460,11 +1169,14 @@ pub fn separate_packages_by_priority(
     if initial_standard_subset.len() != 0 {
         print_message("debug", "calculating standard subset.", &message_config);
 
-        match resolve_dependencies(
-            &package_database,
+        match resolve_dependencies_with(
+            &target_resolver,
+            &subset_package_database,
             &initial_standard_subset,
             &false,
             &packages_to_prohibit,
+            &provider_preferences,
+            &architecture_config,
             &quiet_message_config,
         ) {
             Ok(result) => target_standard_subset = result,
@@ -666,3 +1378,459 @@ pub fn separate_packages_by_priority(
 
     return Ok(());
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn find_satisfying_packages<'a>(
+    package_set: &'a Vec<Package>,
+    relationship: &Relationship,
+    dependent_architecture: &str,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<(&'a Package, bool)> {
+    let mut output: Vec<(&Package, bool)> = Vec::new();
+
+    for package in package_set {
+        if does_architecture_satisfy(
+            package,
+            relationship,
+            dependent_architecture,
+            architecture_config,
+        ) == false
+        {
+            continue;
+        };
+
+        if *package.name == *relationship.name
+            && does_version_satisfy(&package.version, &relationship.version) == true
+        {
+            output.push((package, false));
+        } else if does_package_provide(package, relationship) == true {
+            output.push((package, true));
+        };
+    }
+
+    return output;
+}
+
+pub fn find_chosen_relationships(
+    package: &Package,
+    package_set: &Vec<Package>,
+    consider_recommends: &bool,
+    architecture_config: &ArchitectureConfig,
+) -> Vec<(String, Vec<Relationship>, Relationship, usize, bool)> {
+    let mut relationships_to_check: Vec<(&str, &Vec<Relationship>)> = Vec::new();
+
+    for dependency in &package.pre_depends {
+        relationships_to_check.push(("pre-depends on", dependency));
+    }
+
+    for dependency in &package.depends {
+        relationships_to_check.push(("depends on", dependency));
+    }
+
+    if *consider_recommends == true {
+        for dependency in &package.recommends {
+            relationships_to_check.push(("recommends", dependency));
+        }
+    };
+
+    let mut output: Vec<(String, Vec<Relationship>, Relationship, usize, bool)> = Vec::new();
+
+    // the first alternative that is satisfied inside of the package set is the one that was
+    // chosen, which is how every resolver orders the alternatives.
+    for (kind, dependency) in relationships_to_check {
+        for alternative in dependency {
+            let satisfying_packages: Vec<(&Package, bool)> = find_satisfying_packages(
+                package_set,
+                alternative,
+                &package.architecture,
+                architecture_config,
+            );
+
+            if satisfying_packages.len() != 0 {
+                for (satisfying_package, is_provider) in satisfying_packages {
+                    let index: usize = package_set
+                        .iter()
+                        .position(|element| element == satisfying_package)
+                        .unwrap();
+
+                    output.push((
+                        String::from(kind),
+                        dependency.clone(),
+                        alternative.clone(),
+                        index,
+                        is_provider,
+                    ));
+                }
+                break;
+            };
+        }
+    }
+
+    return output;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn describe_choice(
+    parent: &Package,
+    kind: &str,
+    dependency: &Vec<Relationship>,
+    alternative: &Relationship,
+    chosen: &Package,
+    is_provider: bool,
+) -> String {
+    let mut description: String = format!(
+        "\"{}\" ({}) {kind}: \"{}\"",
+        parent.name,
+        parent.version,
+        format_relationship_alternatives(dependency)
+    );
+
+    if dependency.len() > 1 {
+        description.push_str(&format!(
+            ", alternative: \"{}\"",
+            format_relationship_alternatives(&Vec::from([alternative.clone()]))
+        ));
+    };
+
+    if is_provider == true {
+        description.push_str(&format!(
+            ", provided by: \"{}\" ({})",
+            chosen.name, chosen.version
+        ));
+    } else {
+        description.push_str(&format!(" -> \"{}\" ({})", chosen.name, chosen.version));
+    };
+
+    return description;
+}
+
+pub fn explain_why(
+    package_name: &str,
+    initial_package_set: &Vec<Package>,
+    target_package_set: &Vec<Package>,
+    packages_to_include: &Vec<String>,
+    consider_recommends: &bool,
+    architecture_config: &ArchitectureConfig,
+) -> Result<(), ()> {
+    println!();
+
+    let target_index: usize;
+
+    match target_package_set
+        .iter()
+        .position(|package| package.name == package_name)
+    {
+        Some(result) => target_index = result,
+        None => {
+            println!("\"{package_name}\" is not in the target package set.");
+            return Err(());
+        }
+    };
+
+    println!(
+        "Why \"{}\" ({}) is in the target package set:",
+        target_package_set[target_index].name, target_package_set[target_index].version
+    );
+
+    // a breadth-first search from the initial package set finds the shortest chain.
+    let mut parents: HashMap<usize, (usize, String)> = HashMap::new();
+    let mut indices_to_visit: VecDeque<usize> = VecDeque::new();
+
+    for (index, package) in target_package_set.iter().enumerate() {
+        if initial_package_set.contains(package) == true {
+            parents.insert(index, (index, String::new()));
+            indices_to_visit.push_back(index);
+        };
+    }
+
+    let mut reverse_dependencies: Vec<String> = Vec::new();
+
+    while let Some(index) = indices_to_visit.pop_front() {
+        let package: &Package = &target_package_set[index];
+
+        for (kind, dependency, alternative, chosen_index, is_provider) in find_chosen_relationships(
+            package,
+            target_package_set,
+            consider_recommends,
+            architecture_config,
+        ) {
+            if chosen_index == target_index && index != target_index {
+                let description: String = describe_choice(
+                    package,
+                    &kind,
+                    &dependency,
+                    &alternative,
+                    &target_package_set[chosen_index],
+                    is_provider,
+                );
+
+                if reverse_dependencies.contains(&description) == false {
+                    reverse_dependencies.push(description);
+                };
+            };
+
+            if parents.contains_key(&chosen_index) == false {
+                parents.insert(
+                    chosen_index,
+                    (
+                        index,
+                        describe_choice(
+                            package,
+                            &kind,
+                            &dependency,
+                            &alternative,
+                            &target_package_set[chosen_index],
+                            is_provider,
+                        ),
+                    ),
+                );
+                indices_to_visit.push_back(chosen_index);
+            };
+        }
+    }
+
+    match parents.get(&target_index) {
+        Some(..) => {
+            let mut chain: Vec<String> = Vec::new();
+            let mut index: usize = target_index;
+
+            loop {
+                let (parent_index, description) = &parents[&index];
+
+                if *parent_index == index {
+                    let package: &Package = &target_package_set[index];
+
+                    if packages_to_include.iter().any(|included| {
+                        included.split([':', '=', '/']).next() == Some(&package.name)
+                    }) == true
+                    {
+                        chain.push(format!(
+                            "\"{}\" ({}) was added to the initial package set by --include",
+                            package.name, package.version
+                        ));
+                    } else {
+                        chain.push(format!(
+                            "\"{}\" ({}) is part of the initial package set",
+                            package.name, package.version
+                        ));
+                    };
+                    break;
+                };
+
+                chain.push(description.clone());
+                index = *parent_index;
+            }
+
+            for link in chain.iter().rev() {
+                println!("    {link}");
+            }
+        }
+        None => {
+            println!("    not reachable from the initial package set.");
+        }
+    };
+
+    if reverse_dependencies.len() != 0 {
+        println!("Required by:");
+
+        for description in &reverse_dependencies {
+            println!("    {description}");
+        }
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn explain_why_not(
+    package_name: &str,
+    package_database: &PackageDatabase,
+    target_package_set: &Vec<Package>,
+    packages_to_exclude: &Vec<String>,
+    packages_to_prohibit: &Vec<String>,
+    consider_recommends: &bool,
+    architecture_config: &ArchitectureConfig,
+) -> Result<(), ()> {
+    println!();
+
+    match target_package_set
+        .iter()
+        .find(|package| package.name == package_name)
+    {
+        Some(result) => {
+            println!(
+                "\"{}\" ({}) is in the target package set, see --why.",
+                result.name, result.version
+            );
+            return Err(());
+        }
+        None => {}
+    };
+
+    println!("Why \"{package_name}\" is not in the target package set:");
+
+    let candidates: Vec<&Package> =
+        find_packages_by_name(package_database, package_name, architecture_config);
+
+    if candidates.len() == 0 {
+        println!("    no package with that name is available in the chosen suites.");
+        return Ok(());
+    };
+
+    let mut reasons: Vec<String> = Vec::new();
+
+    if packages_to_prohibit.contains(&String::from(package_name)) == true {
+        reasons.push(String::from("it is prohibited by --prohibit"));
+    };
+
+    if packages_to_exclude.contains(&String::from(package_name)) == true {
+        reasons.push(String::from(
+            "it was removed from the initial package set by --exclude",
+        ));
+    };
+
+    if candidates
+        .iter()
+        .all(|candidate| candidate.pin_priority < 0)
+        == true
+    {
+        reasons.push(String::from(
+            "every version of it has a negative pin priority",
+        ));
+    };
+
+    for candidate in &candidates {
+        for package in target_package_set {
+            match find_conflict(candidate, package, architecture_config) {
+                Some(explanation) => {
+                    if reasons.contains(&explanation) == false {
+                        reasons.push(explanation);
+                    };
+                }
+                None => {}
+            };
+
+            match find_conflict(package, candidate, architecture_config) {
+                Some(explanation) => {
+                    if reasons.contains(&explanation) == false {
+                        reasons.push(explanation);
+                    };
+                }
+                None => {}
+            };
+        }
+    }
+
+    // packages that list it as an alternative, but were satisfied by something else.
+    for package in target_package_set {
+        let mut relationships_to_check: Vec<(&str, &Vec<Relationship>)> = Vec::new();
+
+        for dependency in &package.pre_depends {
+            relationships_to_check.push(("pre-depends on", dependency));
+        }
+
+        for dependency in &package.depends {
+            relationships_to_check.push(("depends on", dependency));
+        }
+
+        if *consider_recommends == true {
+            for dependency in &package.recommends {
+                relationships_to_check.push(("recommends", dependency));
+            }
+        } else {
+            for dependency in &package.recommends {
+                if dependency
+                    .iter()
+                    .any(|alternative| &*alternative.name == package_name)
+                    == true
+                {
+                    let reason: String = format!(
+                        "\"{}\" ({}) only recommends: \"{}\" and recommends aren't considered",
+                        package.name,
+                        package.version,
+                        format_relationship_alternatives(dependency)
+                    );
+
+                    if reasons.contains(&reason) == false {
+                        reasons.push(reason);
+                    };
+                };
+            }
+        };
+
+        for (kind, dependency) in relationships_to_check {
+            if dependency
+                .iter()
+                .any(|alternative| &*alternative.name == package_name)
+                == false
+            {
+                continue;
+            };
+
+            for alternative in dependency {
+                match find_satisfying_packages(
+                    target_package_set,
+                    alternative,
+                    &package.architecture,
+                    architecture_config,
+                )
+                .first()
+                {
+                    Some((chosen, _is_provider)) => {
+                        let reason: String = format!(
+                            "\"{}\" ({}) {kind}: \"{}\", which is satisfied by: \"{}\" ({})",
+                            package.name,
+                            package.version,
+                            format_relationship_alternatives(dependency),
+                            chosen.name,
+                            chosen.version
+                        );
+
+                        if reasons.contains(&reason) == false {
+                            reasons.push(reason);
+                        };
+                        break;
+                    }
+                    None => {}
+                };
+            }
+        }
+    }
+
+    if reasons.len() == 0 {
+        reasons.push(String::from(
+            "it is not part of the initial package set and no package in the target package set requires it",
+        ));
+
+        let mut dependents: Vec<String> = package_database
+            .get_reverse_dependencies(package_name)
+            .iter()
+            .filter(|package| {
+                target_package_set
+                    .iter()
+                    .any(|target| target.name == package.name)
+                    == false
+            })
+            .map(|package| format!("\"{}\"", format_package_name(package, architecture_config)))
+            .collect::<Vec<String>>();
+
+        dependents.sort_unstable();
+        dependents.dedup();
+
+        if dependents.len() != 0 {
+            reasons.push(format!(
+                "it is required by packages outside of the target package set: {}",
+                dependents.join(", ")
+            ));
+        };
+    };
+
+    for reason in &reasons {
+        println!("    {reason}");
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,471 @@
+use crate::functions::*;
+use crate::package::*;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// the cache is only valid for the version of debstrap and the format that wrote it, as the parsing
+// of package lists may change between versions.
+const INDEX_CACHE_MAGIC: &str = "debstrap-index-cache";
+const INDEX_CACHE_FORMAT: &str = "2";
+const INDEX_CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+struct CacheWriter<'a> {
+    buffer: Vec<u8>,
+    string_indices: HashMap<&'a str, u32>,
+    strings: Vec<&'a str>,
+}
+
+impl<'a> CacheWriter<'a> {
+    fn push_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    fn push_u32(&mut self, value: u32) {
+        self.buffer.extend(value.to_le_bytes());
+    }
+
+    fn push_u64(&mut self, value: u64) {
+        self.buffer.extend(value.to_le_bytes());
+    }
+
+    // strings are stored once in a table at the start of the cache and referenced by their index.
+    fn push_string(&mut self, value: &'a str) {
+        let index: u32 = match self.string_indices.get(value) {
+            Some(result) => *result,
+            None => {
+                let index: u32 = self.strings.len() as u32;
+
+                self.string_indices.insert(value, index);
+                self.strings.push(value);
+
+                index
+            }
+        };
+
+        self.push_u32(index);
+    }
+
+    fn push_list_of_strings(&mut self, values: &'a Vec<String>) {
+        self.push_u32(values.len() as u32);
+
+        for value in values {
+            self.push_string(value);
+        }
+    }
+
+    fn push_relationships(&mut self, relationships: &'a Vec<Vec<Relationship>>) {
+        self.push_u32(relationships.len() as u32);
+
+        for alternatives in relationships {
+            self.push_u32(alternatives.len() as u32);
+
+            for relationship in alternatives {
+                self.push_string(&relationship.name);
+                self.push_string(&relationship.version);
+                self.push_string(&relationship.architecture);
+                self.push_list_of_strings(&relationship.architecture_restrictions);
+
+                self.push_u32(relationship.profile_restrictions.len() as u32);
+
+                for profiles in &relationship.profile_restrictions {
+                    self.push_list_of_strings(profiles);
+                }
+            }
+        }
+    }
+}
+
+// the string table only lives as long as the reader, strings are copied out of it when a package is
+// built and only the ones stored as 'Rc<str>' go through the string interner.
+struct CacheReader<'a, 'b> {
+    data: &'a [u8],
+    position: usize,
+    strings: Vec<&'a str>,
+    string_interner: &'b mut StringInterner,
+}
+
+impl<'a, 'b> CacheReader<'a, 'b> {
+    fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], ()> {
+        if self.data.len() - self.position < length {
+            return Err(());
+        };
+
+        let bytes: &[u8] = &self.data[self.position..self.position + length];
+
+        self.position += length;
+
+        return Ok(bytes);
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ()> {
+        return Ok(self.read_bytes(1)?[0]);
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ()> {
+        return Ok(u32::from_le_bytes(
+            self.read_bytes(4)?.try_into().map_err(|_| ())?,
+        ));
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ()> {
+        return Ok(u64::from_le_bytes(
+            self.read_bytes(8)?.try_into().map_err(|_| ())?,
+        ));
+    }
+
+    fn read_raw_string(&mut self) -> Result<&'a str, ()> {
+        let length: usize = self.read_u32()? as usize;
+
+        return std::str::from_utf8(self.read_bytes(length)?).map_err(|_| ());
+    }
+
+    fn read_str(&mut self) -> Result<&'a str, ()> {
+        let index: usize = self.read_u32()? as usize;
+
+        match self.strings.get(index) {
+            Some(result) => return Ok(result),
+            None => return Err(()),
+        };
+    }
+
+    fn read_shared_string(&mut self) -> Result<Rc<str>, ()> {
+        let value: &str = self.read_str()?;
+
+        return Ok(self.string_interner.intern(value));
+    }
+
+    fn read_string(&mut self) -> Result<String, ()> {
+        return Ok(String::from(self.read_str()?));
+    }
+
+    fn read_list_of_strings(&mut self) -> Result<Vec<String>, ()> {
+        let mut output: Vec<String> = Vec::new();
+
+        for _ in 0..self.read_u32()? {
+            output.push(self.read_string()?);
+        }
+
+        return Ok(output);
+    }
+
+    fn read_relationships(&mut self) -> Result<Vec<Vec<Relationship>>, ()> {
+        let mut output: Vec<Vec<Relationship>> = Vec::new();
+
+        for _ in 0..self.read_u32()? {
+            let mut alternatives: Vec<Relationship> = Vec::new();
+
+            for _ in 0..self.read_u32()? {
+                let mut relationship: Relationship = Relationship {
+                    name: self.read_shared_string()?,
+                    version: self.read_string()?,
+                    architecture: self.read_string()?,
+                    architecture_restrictions: self.read_list_of_strings()?,
+                    profile_restrictions: Vec::new(),
+                };
+
+                for _ in 0..self.read_u32()? {
+                    relationship
+                        .profile_restrictions
+                        .push(self.read_list_of_strings()?);
+                }
+
+                alternatives.push(relationship);
+            }
+
+            output.push(alternatives);
+        }
+
+        return Ok(output);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn write_index_cache(
+    cache_file: &str,
+    packages: &Vec<Package>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let mut writer: CacheWriter = CacheWriter {
+        buffer: Vec::new(),
+        string_indices: HashMap::new(),
+        strings: Vec::new(),
+    };
+
+    writer.push_u32(packages.len() as u32);
+
+    // the origin and the pin priority of the packages depend on the sources, not on the package
+    // list, so they aren't cached.
+    for package in packages {
+        writer.push_string(&package.name);
+        writer.push_string(&package.version);
+        writer.push_string(&package.architecture);
+        writer.push_string(&package.source);
+        writer.push_string(&package.source_version);
+        writer.push_string(&package.section);
+        writer.push_string(&package.priority);
+        writer.push_string(&package.multi_arch);
+        writer.push_relationships(&package.depends);
+        writer.push_relationships(&package.pre_depends);
+        writer.push_relationships(&package.recommends);
+        writer.push_relationships(&package.suggests);
+        writer.push_relationships(&package.enhances);
+        writer.push_relationships(&package.breaks);
+        writer.push_relationships(&package.conflicts);
+        writer.push_relationships(&package.provides);
+        writer.push_relationships(&package.replaces);
+        writer.push_u8(package.is_essential as u8);
+        writer.push_u8(package.is_build_essential as u8);
+        writer.push_string(&package.file_name);
+        writer.push_u64(package.file_size);
+        writer.push_u64(package.installed_size);
+        writer.push_string(&package.sha256_hash);
+        writer.push_string(&package.md5_hash);
+        writer.push_string(&package.maintainer);
+        writer.push_string(&package.description);
+        writer.push_string(&package.homepage);
+
+        writer.push_u32(package.other_fields.len() as u32);
+
+        for (name, value) in &package.other_fields {
+            writer.push_string(name);
+            writer.push_string(value);
+        }
+    }
+
+    //////////////////////////////////////
+
+    let mut output: Vec<u8> = Vec::new();
+
+    for value in [INDEX_CACHE_MAGIC, INDEX_CACHE_FORMAT, INDEX_CACHE_VERSION] {
+        output.extend((value.len() as u32).to_le_bytes());
+        output.extend(value.as_bytes());
+    }
+
+    output.extend((writer.strings.len() as u32).to_le_bytes());
+
+    for value in &writer.strings {
+        output.extend((value.len() as u32).to_le_bytes());
+        output.extend(value.as_bytes());
+    }
+
+    output.extend(&writer.buffer);
+
+    // the cache is written next to its final location first, so an interrupted run can't leave a
+    // truncated cache behind.
+    if std::fs::write(format!("{cache_file}.tmp"), &output).is_err() == true
+        || std::fs::rename(format!("{cache_file}.tmp"), cache_file).is_err() == true
+    {
+        print_message(
+            "warning",
+            &format!("failed to write index cache: \"{cache_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    return Ok(());
+}
+
+pub fn read_index_cache(
+    cache_file: &str,
+    origin_suite: &str,
+    origin_component: &str,
+    origin_architecture: &str,
+    origin_uri_scheme: &str,
+    origin_uri_path: &str,
+    string_interner: &mut StringInterner,
+    message_config: &MessageConfig,
+) -> Result<Vec<Package>, ()> {
+    let data: Vec<u8>;
+
+    match std::fs::read(cache_file) {
+        Ok(result) => data = result,
+        Err(..) => {
+            print_message(
+                "warning",
+                &format!("failed to read index cache: \"{cache_file}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    let mut reader: CacheReader = CacheReader {
+        data: &data,
+        position: 0,
+        strings: Vec::new(),
+        string_interner: string_interner,
+    };
+
+    let mut read_packages = || -> Result<Vec<Package>, ()> {
+        if reader.read_raw_string()? != INDEX_CACHE_MAGIC
+            || reader.read_raw_string()? != INDEX_CACHE_FORMAT
+            || reader.read_raw_string()? != INDEX_CACHE_VERSION
+        {
+            return Err(());
+        };
+
+        for _ in 0..reader.read_u32()? {
+            let value: &str = reader.read_raw_string()?;
+
+            reader.strings.push(value);
+        }
+
+        let mut packages: Vec<Package> = Vec::new();
+
+        for _ in 0..reader.read_u32()? {
+            let mut package: Package = Package {
+                name: reader.read_string()?,
+                version: reader.read_string()?,
+                architecture: reader.read_string()?,
+                source: reader.read_string()?,
+                source_version: reader.read_string()?,
+                section: reader.read_shared_string()?,
+                priority: reader.read_string()?,
+                multi_arch: reader.read_string()?,
+                depends: reader.read_relationships()?,
+                pre_depends: reader.read_relationships()?,
+                recommends: reader.read_relationships()?,
+                suggests: reader.read_relationships()?,
+                enhances: reader.read_relationships()?,
+                breaks: reader.read_relationships()?,
+                conflicts: reader.read_relationships()?,
+                provides: reader.read_relationships()?,
+                replaces: reader.read_relationships()?,
+                is_essential: reader.read_u8()? != 0,
+                is_build_essential: reader.read_u8()? != 0,
+                file_name: reader.read_string()?,
+                file_size: reader.read_u64()?,
+                installed_size: reader.read_u64()?,
+                sha256_hash: reader.read_string()?,
+                md5_hash: reader.read_string()?,
+                maintainer: reader.read_shared_string()?,
+                description: reader.read_string()?,
+                homepage: reader.read_string()?,
+                other_fields: Vec::new(),
+                origin_suite: String::from(origin_suite),
+                origin_component: String::from(origin_component),
+                origin_architecture: String::from(origin_architecture),
+                origin_uri_scheme: String::from(origin_uri_scheme),
+                origin_uri_path: String::from(origin_uri_path),
+                pin_priority: 500,
+            };
+
+            for _ in 0..reader.read_u32()? {
+                let name: Rc<str> = reader.read_shared_string()?;
+                let value: String = reader.read_string()?;
+
+                package.other_fields.push((name, value));
+            }
+
+            packages.push(package);
+        }
+
+        if reader.position != reader.data.len() {
+            return Err(());
+        };
+
+        return Ok(packages);
+    };
+
+    match read_packages() {
+        Ok(result) => return Ok(result),
+        Err(..) => {
+            print_message(
+                "warning",
+                &format!("ignoring invalid index cache: \"{cache_file}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deb822::*;
+
+    #[test]
+    fn read_written_cache() {
+        let message_config: MessageConfig = MessageConfig {
+            color: false,
+            debug: false,
+        };
+
+        let mut string_interner: StringInterner = StringInterner::default();
+
+        let (paragraphs, errors) = parse_deb822_paragraphs(
+            "Package: hello\nVersion: 1:2.10-3\nArchitecture: amd64\nSource: hello-src (2.10-2)\nSection: devel\nPriority: optional\nMulti-Arch: foreign\nDepends: libc6 (>= 2.34) [amd64 !i386] <!nocheck> <stage1 cross>, foo:any | bar\nProvides: hello-virtual (= 1.0)\nEssential: yes\nFilename: pool/main/h/hello/hello_2.10-3_amd64.deb\nSize: 53000\nInstalled-Size: 280\nSHA256: 0123456789abcdef\nMD5sum: fedcba9876543210\nMaintainer: Someone <someone@example.org>\nDescription: example package\n with a long description\nHomepage: https://example.org\nTag: devel::lang:c\nPhased-Update-Percentage: 10\n\nPackage: empty\nVersion: 1\nArchitecture: all\n",
+        );
+
+        assert_eq!(errors.len(), 0);
+
+        let packages: Vec<Package> = paragraphs
+            .iter()
+            .map(|paragraph| {
+                Package::new(
+                    paragraph,
+                    "sid",
+                    "main",
+                    "amd64",
+                    "https",
+                    "deb.debian.org/debian",
+                    &mut string_interner,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<Package>>();
+
+        let cache_file: String = format!(
+            "{}/debstrap-test-index-cache-{}",
+            std::env::temp_dir().to_string_lossy(),
+            std::process::id()
+        );
+
+        assert_eq!(
+            write_index_cache(&cache_file, &packages, &message_config),
+            Ok(())
+        );
+
+        let mut read_string_interner: StringInterner = StringInterner::default();
+
+        let read_packages: Result<Vec<Package>, ()> = read_index_cache(
+            &cache_file,
+            "sid",
+            "main",
+            "amd64",
+            "https",
+            "deb.debian.org/debian",
+            &mut read_string_interner,
+            &message_config,
+        );
+
+        std::fs::remove_file(&cache_file).unwrap();
+
+        assert_eq!(read_packages, Ok(packages));
+
+        // a truncated cache is rejected instead of producing partial packages.
+        std::fs::write(&cache_file, b"debstrap").unwrap();
+
+        assert_eq!(
+            read_index_cache(
+                &cache_file,
+                "sid",
+                "main",
+                "amd64",
+                "https",
+                "deb.debian.org/debian",
+                &mut read_string_interner,
+                &message_config,
+            ),
+            Err(())
+        );
+
+        std::fs::remove_file(&cache_file).unwrap();
+    }
+}
@@ -0,0 +1,379 @@
+use crate::functions::*;
+use crate::package::*;
+
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct CopyrightReport {
+    pub package_name: String,
+    pub package_version: String,
+    pub package_architecture: String,
+    pub copyright_file: String,
+    pub status: String,
+    pub licenses: Vec<String>,
+    pub raw_text: String,
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn resolve_path_inside_root(root_directory: &str, path: &str) -> Option<PathBuf> {
+    // symbolic links (absolute or relative) are followed as if the root directory was '/', this
+    // matters since many '/usr/share/doc/<package>' directories are links to another package's.
+    let mut resolved_path: PathBuf = PathBuf::from("/");
+    let mut remaining_components: Vec<String> = Path::new(path)
+        .components()
+        .rev()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(String::from(name.to_string_lossy())),
+            Component::ParentDir => Some(String::from("..")),
+            _ => None,
+        })
+        .collect::<Vec<String>>();
+
+    let mut links_followed: u16 = 0;
+
+    while let Some(component) = remaining_components.pop() {
+        if component == ".." {
+            resolved_path.pop();
+            continue;
+        };
+
+        let candidate_path: PathBuf = resolved_path.join(&component);
+
+        let host_path: String = format!("{root_directory}{}", candidate_path.display());
+
+        match std::fs::read_link(&host_path) {
+            Ok(link_target) => {
+                links_followed += 1;
+
+                if links_followed > 40 {
+                    return None;
+                };
+
+                if link_target.is_absolute() == true {
+                    resolved_path = PathBuf::from("/");
+                };
+
+                for link_component in link_target.components().rev() {
+                    match link_component {
+                        Component::Normal(name) => {
+                            remaining_components.push(String::from(name.to_string_lossy()));
+                        }
+                        Component::ParentDir => remaining_components.push(String::from("..")),
+                        _ => {}
+                    };
+                }
+            }
+            Err(..) => resolved_path = candidate_path,
+        };
+    }
+
+    return Some(PathBuf::from(format!(
+        "{root_directory}{}",
+        resolved_path.display()
+    )));
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn parse_machine_readable_copyright(contents: &str) -> Option<Vec<String>> {
+    let mut lines = contents.lines().skip_while(|line| line.trim().is_empty());
+
+    // a machine-readable (DEP-5) copyright file starts with a 'Format:' field pointing to the
+    // copyright-format specification.
+    match lines.next() {
+        Some(line) if line.starts_with("Format:") == true => {
+            if line.contains("copyright-format") == false && line.contains("dep5") == false {
+                return None;
+            };
+        }
+        _ => return None,
+    };
+
+    let mut licenses: Vec<String> = Vec::new();
+    let mut paragraph_index: u16 = 0;
+    let mut paragraph_has_fields: bool = false;
+    let mut is_files_paragraph: bool = false;
+    let mut paragraph_license: String = String::new();
+
+    for line in contents.lines().chain(std::iter::once("")) {
+        if line.trim().is_empty() == true {
+            if paragraph_has_fields == false {
+                continue;
+            };
+
+            // only the licenses of the header and of 'Files' paragraphs apply, stand-alone
+            // 'License' paragraphs just hold the text of a license that is referenced elsewhere.
+            if (paragraph_index == 0 || is_files_paragraph == true)
+                && paragraph_license.is_empty() == false
+                && licenses.contains(&paragraph_license) == false
+            {
+                licenses.push(paragraph_license.clone());
+            };
+
+            paragraph_index += 1;
+            paragraph_has_fields = false;
+            is_files_paragraph = false;
+            paragraph_license = String::new();
+
+            continue;
+        };
+
+        if line.starts_with(" ") == true || line.starts_with("\t") == true {
+            continue;
+        };
+
+        match line.split_once(':') {
+            Some((field, value)) => {
+                paragraph_has_fields = true;
+
+                match &field.trim().to_lowercase() as &str {
+                    "files" => is_files_paragraph = true,
+                    "license" => paragraph_license = String::from(value.trim()),
+                    _ => {}
+                };
+            }
+            None => {}
+        };
+    }
+
+    return Some(licenses);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn guess_licenses_from_text(contents: &str) -> Vec<String> {
+    let mut licenses: Vec<String> = Vec::new();
+
+    // most non machine-readable copyright files still point to the license texts shipped by the
+    // package 'base-files'.
+    for (index, _match) in contents.match_indices("/usr/share/common-licenses/") {
+        let license: String = contents[index + "/usr/share/common-licenses/".len()..]
+            .chars()
+            .take_while(|character| {
+                character.is_ascii_alphanumeric() == true
+                    || *character == '-'
+                    || *character == '.'
+                    || *character == '+'
+            })
+            .collect::<String>()
+            .trim_end_matches('.')
+            .to_string();
+
+        if license.is_empty() == false && licenses.contains(&license) == false {
+            licenses.push(license);
+        };
+    }
+
+    return licenses;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn collect_copyright_reports(
+    target_directory: &str,
+    target_package_set: &Vec<Package>,
+    message_config: &MessageConfig,
+) -> Vec<CopyrightReport> {
+    let mut reports: Vec<CopyrightReport> = Vec::new();
+
+    for package in target_package_set {
+        let mut report: CopyrightReport = Default::default();
+
+        report.package_name = package.name.clone();
+        report.package_version = package.version.clone();
+        report.package_architecture = package.architecture.clone();
+        report.copyright_file = format!("/usr/share/doc/{}/copyright", package.name);
+
+        let contents: String;
+
+        match resolve_path_inside_root(target_directory, &report.copyright_file) {
+            Some(path) => match std::fs::read(&path) {
+                Ok(result) => contents = String::from_utf8_lossy(&result).into_owned(),
+                Err(..) => {
+                    print_message(
+                        "debug",
+                        &format!("no copyright file found for package: \"{}\"", package.name),
+                        &message_config,
+                    );
+
+                    report.status = String::from("missing");
+                    reports.push(report);
+                    continue;
+                }
+            },
+            None => {
+                report.status = String::from("missing");
+                reports.push(report);
+                continue;
+            }
+        };
+
+        match parse_machine_readable_copyright(&contents) {
+            Some(licenses) => {
+                report.licenses = licenses;
+
+                if report.licenses.is_empty() == true {
+                    report.status = String::from("unparseable");
+                    report.raw_text = contents;
+                } else {
+                    report.status = String::from("machine-readable");
+                };
+            }
+            None => {
+                report.licenses = guess_licenses_from_text(&contents);
+
+                if report.licenses.is_empty() == true {
+                    report.status = String::from("unparseable");
+                } else {
+                    report.status = String::from("raw");
+                };
+
+                report.raw_text = contents;
+            }
+        };
+
+        reports.push(report);
+    }
+
+    reports.sort_unstable();
+
+    return reports;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_license_report(
+    target_directory: &str,
+    target_package_set: &Vec<Package>,
+    output_file: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let summary_file: String = format!(
+        "{}.txt",
+        output_file.strip_suffix(".json").unwrap_or(output_file)
+    );
+
+    print_message(
+        "debug",
+        &format!("creating license report: \"{output_file}\" and \"{summary_file}\""),
+        &message_config,
+    );
+
+    let reports: Vec<CopyrightReport> =
+        collect_copyright_reports(target_directory, target_package_set, &message_config);
+
+    //////////////////////////////////////////////
+
+    let mut entries: Vec<String> = Vec::new();
+
+    for report in &reports {
+        entries.push(format!(
+            "    {{\"name\": \"{}\", \"version\": \"{}\", \"architecture\": \"{}\", \"copyright_file\": \"{}\", \"status\": \"{}\", \"licenses\": [{}], \"flagged\": {}, \"raw_text\": \"{}\"}}",
+            escape_json_string(&report.package_name),
+            escape_json_string(&report.package_version),
+            escape_json_string(&report.package_architecture),
+            escape_json_string(&report.copyright_file),
+            escape_json_string(&report.status),
+            report
+                .licenses
+                .iter()
+                .map(|license| format!("\"{}\"", escape_json_string(license)))
+                .collect::<Vec<String>>()
+                .join(", "),
+            report.licenses.is_empty(),
+            escape_json_string(&report.raw_text),
+        ));
+    }
+
+    if create_file(
+        output_file,
+        &format!("{{\n  \"packages\": [\n{}\n  ]\n}}\n", entries.join(",\n")),
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    //////////////////////////////////////////////
+
+    let mut name_length: usize = "Package".len();
+
+    for report in &reports {
+        if report.package_name.len() > name_length {
+            name_length = report.package_name.len();
+        };
+    }
+
+    let mut summary: String = format!(
+        "License report of {} packages.\n\n{:name_length$}  {:16}  {}\n",
+        reports.len(),
+        "Package",
+        "Status",
+        "Licenses"
+    );
+
+    let mut license_totals: Vec<(String, u64)> = Vec::new();
+    let mut flagged_packages: Vec<String> = Vec::new();
+
+    for report in &reports {
+        summary.push_str(&format!(
+            "{:name_length$}  {:16}  {}\n",
+            report.package_name,
+            report.status,
+            report.licenses.join(", ")
+        ));
+
+        for license in &report.licenses {
+            match license_totals
+                .iter_mut()
+                .find(|(name, _amount)| name == license)
+            {
+                Some((_name, amount)) => *amount += 1,
+                None => license_totals.push((license.clone(), 1)),
+            };
+        }
+
+        if report.licenses.is_empty() == true {
+            flagged_packages.push(format!("{} ({})", report.package_name, report.status));
+        };
+    }
+
+    license_totals.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    summary.push_str("\nLicenses:\n");
+
+    for (license, amount) in &license_totals {
+        summary.push_str(&format!("    {amount:>5}  {license}\n"));
+    }
+
+    summary.push_str(&format!(
+        "\nPackages without a parseable license: {}\n",
+        flagged_packages.len()
+    ));
+
+    for package in &flagged_packages {
+        summary.push_str(&format!("    {package}\n"));
+    }
+
+    if create_file(&summary_file, &summary, None, None, &message_config).is_err() == true {
+        return Err(());
+    };
+
+    if flagged_packages.len() != 0 {
+        print_message(
+            "warning",
+            &format!(
+                "{} package(s) without a parseable license, see: \"{summary_file}\"",
+                flagged_packages.len()
+            ),
+            &message_config,
+        );
+    };
+
+    return Ok(());
+}
@@ -0,0 +1,171 @@
+use crate::package::*;
+
+use std::collections::HashMap;
+
+// the packages of each name and architecture, ordered so that the candidate comes first, together
+// with the indices of which packages provide and depend on a name. the packages are keyed by name
+// and then by architecture so they can be looked up without allocating.
+#[derive(Debug, Default, Clone)]
+pub struct PackageDatabase {
+    packages: HashMap<String, HashMap<String, Vec<Package>>>,
+    keys: Vec<(String, String)>,
+    providers: HashMap<String, Vec<(String, String, usize)>>,
+    reverse_dependencies: HashMap<String, Vec<(String, String)>>,
+}
+
+impl PackageDatabase {
+    // the packages are expected in the order their indices were read, i.e. the order of the
+    // sources.
+    pub fn new(input_packages: Vec<Package>) -> PackageDatabase {
+        let mut ordered_packages: HashMap<(String, String), Vec<(usize, Package)>> = HashMap::new();
+
+        for (source_order, package) in input_packages.into_iter().enumerate() {
+            ordered_packages
+                .entry((package.name.clone(), package.architecture.clone()))
+                .or_default()
+                .push((source_order, package));
+        }
+
+        //////////////////////////////////////
+
+        // like apt, the candidate is the version with the highest pin priority, then the highest
+        // version, and equal versions are taken from the first source they were read from.
+        let mut packages: HashMap<String, HashMap<String, Vec<Package>>> = HashMap::new();
+        let mut keys: Vec<(String, String)> = Vec::new();
+
+        for ((name, architecture), mut entries) in ordered_packages {
+            entries.sort_by(|(a_order, a), (b_order, b)| {
+                return b
+                    .pin_priority
+                    .cmp(&a.pin_priority)
+                    .then(compare_versions(&b.version, &a.version))
+                    .then(a_order.cmp(b_order));
+            });
+
+            keys.push((name.clone(), architecture.clone()));
+
+            packages.entry(name).or_default().insert(
+                architecture,
+                entries
+                    .into_iter()
+                    .map(|(_, package)| package)
+                    .collect::<Vec<Package>>(),
+            );
+        }
+
+        keys.sort_unstable();
+
+        //////////////////////////////////////
+
+        let mut providers: HashMap<String, Vec<(String, String, usize)>> = HashMap::new();
+        let mut reverse_dependencies: HashMap<String, Vec<(String, String)>> = HashMap::new();
+
+        for key in &keys {
+            for (index, package) in packages[&key.0][&key.1].iter().enumerate() {
+                for provided in package.provides.iter().flatten() {
+                    providers
+                        .entry(String::from(&*provided.name))
+                        .or_default()
+                        .push((key.0.clone(), key.1.clone(), index));
+                }
+
+                for relationship in package
+                    .pre_depends
+                    .iter()
+                    .chain(package.depends.iter())
+                    .chain(package.recommends.iter())
+                    .flatten()
+                {
+                    let dependents: &mut Vec<(String, String)> = reverse_dependencies
+                        .entry(String::from(&*relationship.name))
+                        .or_default();
+
+                    if dependents.contains(key) == false {
+                        dependents.push(key.clone());
+                    };
+                }
+            }
+        }
+
+        for entries in providers.values_mut() {
+            entries.sort_unstable();
+            entries.dedup();
+        }
+
+        return PackageDatabase {
+            packages: packages,
+            keys: keys,
+            providers: providers,
+            reverse_dependencies: reverse_dependencies,
+        };
+    }
+
+    pub fn len(&self) -> usize {
+        return self.keys.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.keys.is_empty();
+    }
+
+    // the keys (name and architecture) in sorted order.
+    pub fn keys(&self) -> &Vec<(String, String)> {
+        return &self.keys;
+    }
+
+    pub fn get(&self, package_name: &str, architecture: &str) -> &[Package] {
+        match self
+            .packages
+            .get(package_name)
+            .and_then(|architectures| architectures.get(architecture))
+        {
+            Some(result) => return result,
+            None => return &[],
+        };
+    }
+
+    // a package with a negative pin priority is never a candidate.
+    pub fn get_candidate(&self, package_name: &str, architecture: &str) -> Option<&Package> {
+        return self
+            .get(package_name, architecture)
+            .first()
+            .filter(|package| package.pin_priority >= 0);
+    }
+
+    // the candidates of every name and architecture, in sorted order.
+    pub fn get_candidates(&self) -> Vec<&Package> {
+        return self
+            .keys
+            .iter()
+            .filter_map(|(name, architecture)| self.get_candidate(name, architecture))
+            .collect::<Vec<&Package>>();
+    }
+
+    pub fn get_providers(&self, package_name: &str) -> Vec<&Package> {
+        match self.providers.get(package_name) {
+            Some(result) => {
+                return result
+                    .iter()
+                    .filter_map(|(name, architecture, index)| {
+                        self.get(name, architecture).get(*index)
+                    })
+                    .collect::<Vec<&Package>>();
+            }
+            None => return Vec::new(),
+        };
+    }
+
+    // the candidates of the packages that pre-depend on, depend on, or recommend a name in any of
+    // their versions.
+    pub fn get_reverse_dependencies(&self, package_name: &str) -> Vec<&Package> {
+        match self.reverse_dependencies.get(package_name) {
+            Some(result) => {
+                return result
+                    .iter()
+                    .filter_map(|(name, architecture)| self.get_candidate(name, architecture))
+                    .collect::<Vec<&Package>>();
+            }
+            None => return Vec::new(),
+        };
+    }
+}
@@ -0,0 +1,234 @@
+// a field of a deb822 paragraph, the continuation lines of a multi-line field are kept in its value
+// without their leading whitespace.
+#[derive(Debug, Default, Clone)]
+pub struct Field {
+    pub name: String,
+    pub value: String,
+    pub line_number: usize,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Paragraph {
+    pub fields: Vec<Field>,
+    pub line_number: usize,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Deb822Error {
+    pub line_number: usize,
+    pub message: String,
+}
+
+impl Paragraph {
+    // field names are case-insensitive.
+    pub fn get_field(&self, name: &str) -> Option<&Field> {
+        return self
+            .fields
+            .iter()
+            .find(|field| field.name.eq_ignore_ascii_case(name) == true);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        return self.get_field(name).map(|field| field.value.as_str());
+    }
+
+    // the lines of a folded field like 'Depends' are joined into a single line.
+    pub fn get_folded(&self, name: &str) -> Option<String> {
+        return self.get(name).map(|value| {
+            value
+                .lines()
+                .map(|line| line.trim())
+                .filter(|line| line.is_empty() == false)
+                .collect::<Vec<&str>>()
+                .join(" ")
+        });
+    }
+
+    // the continuation lines of a multi-line field like 'SHA256', with their line numbers.
+    pub fn get_lines(&self, name: &str) -> Vec<(usize, &str)> {
+        match self.get_field(name) {
+            Some(field) => {
+                return field
+                    .value
+                    .lines()
+                    .enumerate()
+                    .skip(1)
+                    .map(|(index, line)| (field.line_number + index, line))
+                    .collect::<Vec<(usize, &str)>>();
+            }
+            None => return Vec::new(),
+        };
+    }
+
+    pub fn error(&self, name: &str, message: &str) -> Deb822Error {
+        return Deb822Error {
+            line_number: match self.get_field(name) {
+                Some(field) => field.line_number,
+                None => self.line_number,
+            },
+            message: String::from(message),
+        };
+    }
+}
+
+pub fn format_deb822_error(file_name: &str, error: &Deb822Error) -> String {
+    return format!("{file_name}:{}: {}", error.line_number, error.message);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// a malformed paragraph is left out of the output and reported, the following paragraphs are still
+// parsed.
+pub fn parse_deb822_paragraphs(input: &str) -> (Vec<Paragraph>, Vec<Deb822Error>) {
+    let mut paragraphs: Vec<Paragraph> = Vec::new();
+    let mut errors: Vec<Deb822Error> = Vec::new();
+
+    let mut current_paragraph: Paragraph = Paragraph::default();
+    let mut is_paragraph_valid: bool = true;
+
+    for (index, line) in input.lines().chain([""]).enumerate() {
+        let line_number: usize = index + 1;
+
+        if line.trim().is_empty() == true {
+            if current_paragraph.fields.len() != 0 && is_paragraph_valid == true {
+                paragraphs.push(current_paragraph);
+            };
+
+            current_paragraph = Paragraph::default();
+            is_paragraph_valid = true;
+
+            continue;
+        };
+
+        if is_paragraph_valid == false {
+            continue;
+        };
+
+        if current_paragraph.fields.len() == 0 {
+            current_paragraph.line_number = line_number;
+        };
+
+        if line.starts_with("#") == true {
+            continue;
+        };
+
+        if line.starts_with(" ") == true || line.starts_with("\t") == true {
+            match current_paragraph.fields.last_mut() {
+                Some(field) => {
+                    field.value.push('\n');
+                    field.value.push_str(line[1..].trim_end());
+                }
+                None => {
+                    errors.push(Deb822Error {
+                        line_number: line_number,
+                        message: String::from("continuation line without a field"),
+                    });
+                    is_paragraph_valid = false;
+                }
+            };
+
+            continue;
+        };
+
+        match line.split_once(':') {
+            Some((name, value))
+                if name.is_empty() == false && name.contains(char::is_whitespace) == false =>
+            {
+                if current_paragraph.get_field(name).is_some() == true {
+                    errors.push(Deb822Error {
+                        line_number: line_number,
+                        message: format!("duplicate field: \"{name}\""),
+                    });
+                    is_paragraph_valid = false;
+                    continue;
+                };
+
+                current_paragraph.fields.push(Field {
+                    name: String::from(name),
+                    value: String::from(value.trim()),
+                    line_number: line_number,
+                });
+            }
+            _ => {
+                errors.push(Deb822Error {
+                    line_number: line_number,
+                    message: format!("invalid field: \"{}\"", line.trim()),
+                });
+                is_paragraph_valid = false;
+            }
+        };
+    }
+
+    return (paragraphs, errors);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_continuation_lines() {
+        let (paragraphs, errors) = parse_deb822_paragraphs(
+            "Package: hello\nDepends: libc6 (>= 2.34),\n libgcc-s1\nDescription: example\n second line\n .\n\tthird line\n",
+        );
+
+        assert_eq!(errors.len(), 0);
+        assert_eq!(paragraphs.len(), 1);
+
+        let paragraph: &Paragraph = &paragraphs[0];
+
+        assert_eq!(paragraph.get("package"), Some("hello"));
+        assert_eq!(
+            paragraph.get_folded("Depends"),
+            Some(String::from("libc6 (>= 2.34), libgcc-s1"))
+        );
+        assert_eq!(
+            paragraph.get("Description"),
+            Some("example\nsecond line\n.\nthird line")
+        );
+        assert_eq!(
+            paragraph.get_lines("Description"),
+            Vec::from([(5, "second line"), (6, "."), (7, "third line")])
+        );
+        assert_eq!(paragraph.get("Version"), None);
+    }
+
+    #[test]
+    fn skip_malformed_paragraphs() {
+        let (paragraphs, errors) = parse_deb822_paragraphs(
+            "# comment\nPackage: a\n\n\nPackage: b\nthis is not a field\nVersion: 1\n\n continuation\nPackage: c\n\nPackage: d\n",
+        );
+
+        assert_eq!(
+            paragraphs
+                .iter()
+                .map(|paragraph| (paragraph.get("Package").unwrap(), paragraph.line_number))
+                .collect::<Vec<(&str, usize)>>(),
+            Vec::from([("a", 2), ("d", 12)])
+        );
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| format_deb822_error("Packages", error))
+                .collect::<Vec<String>>(),
+            Vec::from([
+                String::from("Packages:6: invalid field: \"this is not a field\""),
+                String::from("Packages:9: continuation line without a field"),
+            ])
+        );
+    }
+
+    #[test]
+    fn reject_duplicate_fields() {
+        let (paragraphs, errors) =
+            parse_deb822_paragraphs("Package: a\nVersion: 1\nversion: 2\n\nPackage: b\n");
+
+        assert_eq!(paragraphs.len(), 1);
+        assert_eq!(paragraphs[0].get("Package"), Some("b"));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line_number, 3);
+        assert_eq!(errors[0].message, "duplicate field: \"version\"");
+    }
+}
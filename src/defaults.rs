@@ -297,3 +297,46 @@ pub fn default_hostname(suite: &str) -> String {
 
     return hostname;
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn default_root_partition_type(architecture: &str) -> String {
+    let partition_type: String;
+
+    // root partition type GUIDs from the UAPI Group's Discoverable Partitions Specification.
+    match architecture {
+        "amd64" => partition_type = String::from("4f68bce3-e8cd-4db1-96e7-fbcaf984b709"),
+        "arm64" => partition_type = String::from("b921b045-1df0-41c3-af44-4c6f280d3fae"),
+        "armel" | "armhf" => partition_type = String::from("69dad710-2ce4-4e3c-b16c-21a1d49abed3"),
+        "i386" => partition_type = String::from("44479540-f297-41b2-9af7-d131d5f0458a"),
+        "loong64" => partition_type = String::from("77055800-792c-4f94-b39a-98c91b762bb6"),
+        "mips64el" => partition_type = String::from("d113af76-80ef-41b4-bdb6-0cff4d3d4a25"),
+        "mipsel" => partition_type = String::from("37c58c8a-d913-4156-a25f-48b1b64e07f0"),
+        "ppc64" => partition_type = String::from("912ade1d-a839-4913-8964-a10eee08fbd2"),
+        "ppc64el" => partition_type = String::from("c31c45e6-3f39-412e-80fb-4809c4980599"),
+        "riscv64" => partition_type = String::from("72ec70a6-cf74-40e6-bd49-4bda08e8f224"),
+        "s390x" => partition_type = String::from("5eead9a9-fe09-4a1e-a1d7-520d00531306"),
+        // generic "Linux filesystem data" for everything else.
+        _ => partition_type = String::from("0fc63daf-8483-4772-8e79-3d69d8477de4"),
+    };
+
+    return partition_type;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn default_distribution_name(suite: &str) -> String {
+    let mut distribution_name: String = String::new();
+
+    if DEBIAN_CURRENT_SUITES.contains(&suite) == true
+        || DEBIAN_OBSOLUTE_SUITES.contains(&suite) == true
+    {
+        distribution_name = String::from("debian");
+    } else if UBUNTU_CURRENT_SUITES.contains(&suite) == true
+        || UBUNTU_OBSOLETE_SUITES.contains(&suite) == true
+    {
+        distribution_name = String::from("ubuntu");
+    };
+
+    return distribution_name;
+}
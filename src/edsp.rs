@@ -0,0 +1,296 @@
+use crate::database::*;
+use crate::functions::*;
+use crate::package::*;
+
+use cmd_lib::run_fun;
+use std::collections::HashMap;
+
+fn format_relationships(relationships: &Vec<Vec<Relationship>>) -> String {
+    return relationships
+        .iter()
+        .map(|alternatives| format_relationship_alternatives(alternatives))
+        .collect::<Vec<String>>()
+        .join(", ");
+}
+
+fn format_install_request(package: &Package, primary_architecture: &str) -> String {
+    // packages of the native architecture (and 'all') are requested without qualifier.
+    if package.architecture == primary_architecture || package.architecture == "all" {
+        return package.name.clone();
+    };
+
+    return format!("{}:{}", package.name, package.architecture);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_edsp_scenario(
+    package_database: &PackageDatabase,
+    input_package_set: &Vec<Package>,
+    consider_recommends: &bool,
+    packages_to_prohibit: &Vec<String>,
+    primary_architecture: &str,
+    list_of_architectures: &Vec<String>,
+) -> (String, Vec<Package>) {
+    let mut scenario: String = String::new();
+
+    let mut install_requests: Vec<String> = input_package_set
+        .iter()
+        .map(|package| format_install_request(package, primary_architecture))
+        .collect::<Vec<String>>();
+
+    install_requests.sort_unstable();
+    install_requests.dedup();
+
+    scenario.push_str(&format!(
+        "\
+Request: EDSP 0.5
+Architecture: {primary_architecture}
+Architectures: {}
+Install: {}
+Install-Recommends: {}
+Install-Suggests: no
+
+",
+        list_of_architectures.join(" "),
+        install_requests.join(" "),
+        match *consider_recommends {
+            true => "yes",
+            false => "no",
+        },
+    ));
+
+    // the universe only consists of packages that aren't prohibited, the solver can't choose what
+    // it doesn't know about.
+    let mut universe: Vec<Package> = Vec::new();
+
+    for (name, architecture) in package_database.keys() {
+        if packages_to_prohibit.contains(name) == true {
+            continue;
+        };
+
+        let packages: &[Package] = package_database.get(name, architecture);
+
+        for (index, package) in packages.iter().enumerate() {
+            // the package database is already ordered by pin priority and version.
+            let is_candidate: bool = index == 0 && package.pin_priority >= 0;
+
+            let mut stanza: String = format!(
+                "\
+Package: {}
+Architecture: {}
+Version: {}
+APT-ID: {}
+Source: {}
+Source-Version: {}
+",
+                package.name,
+                package.architecture,
+                package.version,
+                universe.len(),
+                package.source,
+                package.source_version,
+            );
+
+            if package.priority.is_empty() == false {
+                stanza.push_str(&format!("Priority: {}\n", package.priority));
+            };
+
+            if package.section.is_empty() == false {
+                stanza.push_str(&format!("Section: {}\n", package.section));
+            };
+
+            if package.is_essential == true {
+                stanza.push_str("Essential: yes\n");
+            };
+
+            if package.multi_arch.is_empty() == false {
+                stanza.push_str(&format!("Multi-Arch: {}\n", package.multi_arch));
+            };
+
+            stanza.push_str(&format!(
+                "APT-Release:\n a={},c={},b={}\nAPT-Pin: {}\n",
+                package.origin_suite,
+                package.origin_component,
+                package.origin_architecture,
+                package.pin_priority
+            ));
+
+            if is_candidate == true {
+                stanza.push_str("APT-Candidate: yes\n");
+            };
+
+            for (field, relationships) in [
+                ("Pre-Depends", &package.pre_depends),
+                ("Depends", &package.depends),
+                ("Recommends", &package.recommends),
+                ("Suggests", &package.suggests),
+                ("Enhances", &package.enhances),
+                ("Breaks", &package.breaks),
+                ("Conflicts", &package.conflicts),
+                ("Provides", &package.provides),
+                ("Replaces", &package.replaces),
+            ] {
+                if relationships.len() != 0 {
+                    stanza.push_str(&format!(
+                        "{field}: {}\n",
+                        format_relationships(relationships)
+                    ));
+                };
+            }
+
+            scenario.push_str(&stanza);
+            scenario.push_str("\n");
+
+            universe.push(package.clone());
+        }
+    }
+
+    return (scenario, universe);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn solve_dependencies_externally(
+    solver_location: &str,
+    workspace_directory: &str,
+    package_database: &PackageDatabase,
+    input_package_set: &Vec<Package>,
+    consider_recommends: &bool,
+    packages_to_prohibit: &Vec<String>,
+    primary_architecture: &str,
+    list_of_architectures: &Vec<String>,
+    message_config: &MessageConfig,
+) -> Result<Vec<Package>, ()> {
+    let (scenario, universe) = create_edsp_scenario(
+        package_database,
+        input_package_set,
+        consider_recommends,
+        packages_to_prohibit,
+        primary_architecture,
+        list_of_architectures,
+    );
+
+    let scenario_file: String = format!("{workspace_directory}/edsp_scenario");
+
+    if create_file(&scenario_file, &scenario, None, None, &message_config).is_err() == true {
+        return Err(());
+    };
+
+    print_message(
+        "debug",
+        &format!(
+            "running external solver: \"{solver_location}\" with: \"{}\" packages",
+            universe.len()
+        ),
+        &message_config,
+    );
+
+    let response: String;
+
+    match run_fun!(cat "$scenario_file" | /usr/bin/env "$solver_location") {
+        Ok(result) => response = result,
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to run external solver: \"{solver_location}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    //////////////////////////////////////////////
+
+    let mut output: Vec<Package> = Vec::new();
+    let mut was_error_returned: bool = false;
+
+    for stanza in response.split("\n\n") {
+        let mut fields: HashMap<&str, String> = HashMap::new();
+        let mut current_field: &str = "";
+
+        for line in stanza.lines() {
+            if line.starts_with(" ") == true || line.starts_with("\t") == true {
+                match fields.get_mut(current_field) {
+                    Some(value) => {
+                        value.push_str(&format!("\n{}", line.trim()));
+                    }
+                    None => {}
+                };
+
+                continue;
+            };
+
+            match line.split_once(':') {
+                Some((field, value)) => {
+                    current_field = field;
+                    fields.insert(field, String::from(value.trim()));
+                }
+                None => {}
+            };
+        }
+
+        match fields.get("Install") {
+            Some(value) => match value.parse::<usize>() {
+                Ok(index) if index < universe.len() => {
+                    output.push(universe[index].clone());
+                }
+                _ => {
+                    print_message(
+                        "error",
+                        &format!("external solver returned an invalid APT-ID: \"{value}\""),
+                        &message_config,
+                    );
+                    was_error_returned = true;
+                }
+            },
+            None => {}
+        };
+
+        match fields.get("Error") {
+            Some(value) => {
+                print_message(
+                    "error",
+                    &format!(
+                        "external solver failed ({value}): {}",
+                        fields
+                            .get("Message")
+                            .unwrap_or(&String::new())
+                            .replace("\n.\n", "\n\n")
+                    ),
+                    &message_config,
+                );
+                was_error_returned = true;
+            }
+            None => {}
+        };
+
+        match fields.get("Progress") {
+            Some(value) => {
+                print_message(
+                    "debug",
+                    &format!(
+                        "external solver progress: \"{value}\" {}",
+                        fields.get("Message").unwrap_or(&String::new())
+                    ),
+                    &message_config,
+                );
+            }
+            None => {}
+        };
+    }
+
+    if was_error_returned == true {
+        return Err(());
+    };
+
+    output.sort_unstable();
+    output.dedup();
+
+    if output.len() == 0 {
+        print_message("error", "failed to resolve dependencies.", &message_config);
+        return Err(());
+    };
+
+    return Ok(output);
+}
@@ -1,4 +1,5 @@
 use cmd_lib::{run_cmd, run_fun};
+use rand::RngExt;
 use std::error::Error;
 use std::fs::OpenOptions;
 use std::io::{Cursor, Write};
@@ -69,6 +70,127 @@ pub fn parse_list_of_values(prefix: &str, input: &str) -> Vec<String> {
     return value;
 }
 
+pub fn parse_list_of_packages(prefix: &str, input: &str) -> Vec<String> {
+    let mut output: Vec<String> = Vec::new();
+    let mut current_value: String = String::new();
+    let mut depth: u8 = 0;
+
+    // like 'parse_list_of_values', but restrictions (e.g. "grub-pc [amd64 i386] <!stage1>") are
+    // kept together with the package they follow.
+    for character in input.replacen(prefix, "", 1).chars() {
+        match character {
+            '[' | '<' => {
+                if depth == 0 && current_value.is_empty() == true {
+                    match output.pop() {
+                        Some(previous_value) => current_value = format!("{previous_value} "),
+                        None => {}
+                    };
+                };
+
+                depth += 1;
+                current_value.push(character);
+            }
+            ']' | '>' => {
+                depth = depth.saturating_sub(1);
+                current_value.push(character);
+            }
+            ',' => {
+                if depth == 0 {
+                    if current_value.trim().is_empty() == false {
+                        output.push(String::from(current_value.trim()));
+                    };
+
+                    current_value = String::new();
+                } else {
+                    current_value.push(' ');
+                };
+            }
+            _ if character.is_whitespace() == true => {
+                if depth == 0 {
+                    if current_value.trim().is_empty() == false {
+                        output.push(String::from(current_value.trim()));
+                    };
+
+                    current_value = String::new();
+                } else {
+                    current_value.push(' ');
+                };
+            }
+            _ => current_value.push(character),
+        };
+    }
+
+    if current_value.trim().is_empty() == false {
+        output.push(String::from(current_value.trim()));
+    };
+
+    return output;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_source_date_epoch(message_config: &MessageConfig) -> Result<Option<u64>, ()> {
+    match std::env::var("SOURCE_DATE_EPOCH") {
+        Ok(result) => match result.trim().parse::<u64>() {
+            Ok(epoch) => return Ok(Some(epoch)),
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("invalid value of environment variable: \"SOURCE_DATE_EPOCH\" (\"{result}\")"),
+                    &message_config,
+                );
+                return Err(());
+            }
+        },
+        Err(..) => return Ok(None),
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn format_uuid(bytes: &[u8]) -> String {
+    let hex: String = bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+
+    return format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    );
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn generate_uuid() -> String {
+    let mut bytes: [u8; 16] = rand::rng().random();
+
+    // mark it as a random (version 4, variant 1) UUID.
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    return format_uuid(&bytes);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn derive_uuid(seed: &str) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut bytes: Vec<u8> = Sha256::digest(seed.as_bytes())[0..16].to_vec();
+
+    // the UUID is a truncated SHA256 hash of the seed and not a name-based UUID as defined by
+    // RFC 4122, so it is marked as a custom (version 8, variant 1) UUID.
+    bytes[6] = (bytes[6] & 0x0f) | 0x80;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    return format_uuid(&bytes);
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn space_and_truncate_string(input_string: &str, output_length: u16) -> String {
@@ -169,6 +291,236 @@ pub fn get_debian_architecture_name(architecture: &str) -> Result<String, ()> {
     return Ok(debian_architecture_name);
 }
 
+pub fn get_debian_architecture_tuple(architecture: &str) -> Result<[String; 4], ()> {
+    let debian_architecture_name: String;
+
+    match get_debian_architecture_name(architecture) {
+        Ok(result) => debian_architecture_name = result,
+        Err(..) => return Err(()),
+    };
+
+    // the (abi, libc, os, cpu) tuple of dpkg's 'tupletable', every supported architecture is a
+    // gnu/linux one.
+    let (abi, cpu): (&str, &str) = match &debian_architecture_name as &str {
+        "armel" => ("eabi", "arm"),
+        "armhf" => ("eabihf", "arm"),
+        "mips64el" => ("abi64", "mips64el"),
+        "x32" => ("x32", "amd64"),
+        _ => ("base", &debian_architecture_name as &str),
+    };
+
+    return Ok([
+        String::from(abi),
+        String::from("gnu"),
+        String::from("linux"),
+        String::from(cpu),
+    ]);
+}
+
+pub fn does_architecture_match_wildcard(architecture: &str, wildcard: &str) -> bool {
+    if architecture == wildcard {
+        return true;
+    };
+
+    let architecture_tuple: [String; 4];
+
+    match get_debian_architecture_tuple(architecture) {
+        Ok(result) => architecture_tuple = result,
+        Err(..) => return false,
+    };
+
+    // a wildcard is either an architecture name, or a tuple in which 'any' matches every value,
+    // e.g. "linux-any" (os-cpu), "any-arm", or "gnu-linux-any" (libc-os-cpu).
+    let wildcard_tuple: [String; 4];
+
+    match get_debian_architecture_tuple(wildcard) {
+        Ok(result) => wildcard_tuple = result,
+        Err(..) => {
+            let parts: Vec<&str> = wildcard.split('-').collect::<Vec<&str>>();
+
+            match parts.len() {
+                1 if parts[0] == "any" => {
+                    wildcard_tuple = [
+                        String::from("any"),
+                        String::from("any"),
+                        String::from("any"),
+                        String::from("any"),
+                    ];
+                }
+                2 => {
+                    wildcard_tuple = [
+                        String::from("any"),
+                        String::from("any"),
+                        String::from(parts[0]),
+                        String::from(parts[1]),
+                    ];
+                }
+                3 => {
+                    wildcard_tuple = [
+                        String::from("any"),
+                        String::from(parts[0]),
+                        String::from(parts[1]),
+                        String::from(parts[2]),
+                    ];
+                }
+                4 => {
+                    wildcard_tuple = [
+                        String::from(parts[0]),
+                        String::from(parts[1]),
+                        String::from(parts[2]),
+                        String::from(parts[3]),
+                    ];
+                }
+                _ => return false,
+            };
+        }
+    };
+
+    return architecture_tuple
+        .iter()
+        .zip(wildcard_tuple.iter())
+        .all(|(value, pattern)| pattern == "any" || pattern == value);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// matches the '*' and '?' wildcards, like apt_preferences(5) and the shell do. the bytes are
+// compared directly, as this runs for every line of a Contents index, so '?' and a retried '*'
+// step over the continuation bytes of a multi-byte character.
+pub fn does_value_match_pattern(value: &str, pattern: &str) -> bool {
+    let value: &[u8] = value.as_bytes();
+    let pattern: &[u8] = pattern.as_bytes();
+
+    let character_length = |index: usize| -> usize {
+        return 1 + value[index + 1..]
+            .iter()
+            .take_while(|byte| (**byte & 0xc0) == 0x80)
+            .count();
+    };
+
+    let mut value_index: usize = 0;
+    let mut pattern_index: usize = 0;
+    let mut last_wildcard: Option<(usize, usize)> = None;
+
+    while value_index < value.len() {
+        if pattern_index < pattern.len() && pattern[pattern_index] == b'?' {
+            value_index += character_length(value_index);
+            pattern_index += 1;
+        } else if pattern_index < pattern.len() && pattern[pattern_index] == value[value_index] {
+            value_index += 1;
+            pattern_index += 1;
+        } else if pattern_index < pattern.len() && pattern[pattern_index] == b'*' {
+            last_wildcard = Some((pattern_index, value_index));
+            pattern_index += 1;
+        } else {
+            match last_wildcard {
+                Some((wildcard_index, matched_index)) => {
+                    let next_index: usize = matched_index + character_length(matched_index);
+
+                    pattern_index = wildcard_index + 1;
+                    value_index = next_index;
+                    last_wildcard = Some((wildcard_index, next_index));
+                }
+                None => return false,
+            };
+        };
+    }
+
+    return pattern[pattern_index..].iter().all(|byte| *byte == b'*');
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_oci_architecture_name(architecture: &str) -> (String, String) {
+    let oci_architecture_name: String;
+    let oci_architecture_variant: String;
+
+    // OCI images use the GOARCH (and GOARM) naming instead of the Debian naming.
+    match architecture {
+        "arm64" => {
+            oci_architecture_name = String::from("arm64");
+            oci_architecture_variant = String::from("v8");
+        }
+        "armel" => {
+            oci_architecture_name = String::from("arm");
+            oci_architecture_variant = String::from("v5");
+        }
+        "armhf" => {
+            oci_architecture_name = String::from("arm");
+            oci_architecture_variant = String::from("v7");
+        }
+        "i386" => {
+            oci_architecture_name = String::from("386");
+            oci_architecture_variant = String::new();
+        }
+        "mips64el" => {
+            oci_architecture_name = String::from("mips64le");
+            oci_architecture_variant = String::new();
+        }
+        "mipsel" => {
+            oci_architecture_name = String::from("mipsle");
+            oci_architecture_variant = String::new();
+        }
+        "ppc64el" => {
+            oci_architecture_name = String::from("ppc64le");
+            oci_architecture_variant = String::new();
+        }
+        _ => {
+            oci_architecture_name = String::from(architecture);
+            oci_architecture_variant = String::new();
+        }
+    };
+
+    return (oci_architecture_name, oci_architecture_variant);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn escape_json_string(input_string: &str) -> String {
+    let mut output_string: String = String::new();
+
+    for character in input_string.chars() {
+        match character {
+            '"' => output_string.push_str("\\\""),
+            '\\' => output_string.push_str("\\\\"),
+            '\n' => output_string.push_str("\\n"),
+            '\r' => output_string.push_str("\\r"),
+            '\t' => output_string.push_str("\\t"),
+            character if (character as u32) < 0x20 => {
+                output_string.push_str(&format!("\\u{:04x}", character as u32));
+            }
+            _ => output_string.push(character),
+        };
+    }
+
+    return output_string;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_utc_timestamp(
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<String, ()> {
+    let timestamp: Result<String, std::io::Error>;
+
+    match source_date_epoch {
+        Some(epoch) => {
+            let date: String = format!("@{epoch}");
+            timestamp = run_fun!(date --utc --date="$date" "+%Y-%m-%dT%H:%M:%SZ");
+        }
+        None => timestamp = run_fun!(date --utc "+%Y-%m-%dT%H:%M:%SZ"),
+    };
+
+    match timestamp {
+        Ok(result) => return Ok(result),
+        Err(..) => {
+            print_message("error", "failed to get the current time.", &message_config);
+            return Err(());
+        }
+    };
+}
+
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn create_file(
@@ -511,6 +863,130 @@ pub fn download_file(
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+pub fn resolve_output_file_location(
+    file_location: &str,
+    message_config: &MessageConfig,
+) -> Result<String, ()> {
+    let provided_path = Path::new(file_location);
+
+    if provided_path.exists() == true {
+        print_message(
+            "error",
+            &format!("file: \"{}\" already exists.", provided_path.display()),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    let mut parent_dir: String = String::new();
+
+    match provided_path.parent() {
+        Some(result) => parent_dir = String::from(result.to_string_lossy()),
+        None => {}
+    };
+
+    if parent_dir.is_empty() == true {
+        parent_dir = String::from(".");
+    };
+
+    match (
+        Path::new(&parent_dir).canonicalize(),
+        provided_path.file_name(),
+    ) {
+        (Ok(directory), Some(file_name)) if directory.is_dir() == true => {
+            return Ok(format!(
+                "{}/{}",
+                directory.to_string_lossy(),
+                file_name.to_string_lossy()
+            ));
+        }
+        (Ok(..), None) => {
+            print_message(
+                "error",
+                &format!("invalid file name: \"{file_location}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+        _ => {
+            print_message(
+                "error",
+                &format!("directory: \"{parent_dir}\" does not exist."),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn calculate_file_checksum(
+    hash_type: &str,
+    file_location: &str,
+    message_config: &MessageConfig,
+) -> Result<String, ()> {
+    print_message(
+        "debug",
+        &format!("calculating {hash_type} checksum of file: \"{file_location}\""),
+        &message_config,
+    );
+
+    let mut file: std::fs::File;
+
+    match std::fs::File::open(file_location) {
+        Ok(result) => file = result,
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read file: \"{file_location}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    // the file is streamed through the hasher since it can be much larger than a package.
+    let checksum: String;
+
+    match hash_type {
+        "sha256" => {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            if std::io::copy(&mut file, &mut hasher).is_err() == true {
+                print_message(
+                    "error",
+                    &format!("failed to read file: \"{file_location}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+            checksum = format!("{:x}", hasher.finalize());
+        }
+        "md5" => {
+            use md5::{Digest, Md5};
+            let mut hasher = Md5::new();
+            if std::io::copy(&mut file, &mut hasher).is_err() == true {
+                print_message(
+                    "error",
+                    &format!("failed to read file: \"{file_location}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+            checksum = format!("{:x}", hasher.finalize());
+        }
+        _ => {
+            print_message("error", "invalid hash type.", &message_config);
+            return Err(());
+        }
+    };
+
+    return Ok(checksum);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 pub fn verify_file_checksum(
     hash_type: &str,
     file_location: &str,
@@ -0,0 +1,203 @@
+use crate::algorithms::*;
+use crate::functions::*;
+use crate::package::*;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
+struct Edge {
+    from: String,
+    to: String,
+    kind: String,
+    relation: String,
+}
+
+fn escape_dot_string(input_string: &str) -> String {
+    return input_string.replace("\\", "\\\\").replace("\"", "\\\"");
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_dependency_graph(
+    target_package_set: &Vec<Package>,
+    output_file: &str,
+    graph_format: &str,
+    architecture_config: &ArchitectureConfig,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating {graph_format} dependency graph: \"{output_file}\""),
+        &message_config,
+    );
+
+    let mut edges: Vec<Edge> = Vec::new();
+    let mut virtual_packages: Vec<String> = Vec::new();
+
+    // nodes of foreign packages are qualified by their architecture, e.g. "libc6:i386".
+    //
+    // the edges follow the alternatives that are satisfied inside of the target package set, so
+    // they match the choices of the resolver.
+    for package in target_package_set {
+        for (kind, dependency, alternative, chosen_index, is_provider) in
+            find_chosen_relationships(package, target_package_set, &true, architecture_config)
+        {
+            let kind: &str = match &kind as &str {
+                "pre-depends on" => "Pre-Depends",
+                "recommends" => "Recommends",
+                _ => "Depends",
+            };
+
+            let chosen: &Package = &target_package_set[chosen_index];
+
+            if is_provider == true {
+                if virtual_packages.contains(&String::from(&*alternative.name)) == false {
+                    virtual_packages.push(String::from(&*alternative.name));
+                };
+
+                edges.push(Edge {
+                    from: format_package_name(package, architecture_config),
+                    to: String::from(&*alternative.name),
+                    kind: String::from(kind),
+                    relation: format_relationship_alternatives(&dependency),
+                });
+
+                edges.push(Edge {
+                    from: format_package_name(chosen, architecture_config),
+                    to: String::from(&*alternative.name),
+                    kind: String::from("Provides"),
+                    relation: String::from(&*alternative.name),
+                });
+            } else {
+                edges.push(Edge {
+                    from: format_package_name(package, architecture_config),
+                    to: format_package_name(chosen, architecture_config),
+                    kind: String::from(kind),
+                    relation: format_relationship_alternatives(&dependency),
+                });
+            };
+        }
+    }
+
+    edges.sort_unstable();
+    edges.dedup();
+
+    virtual_packages.sort_unstable();
+
+    let mut packages: Vec<&Package> = target_package_set.iter().collect::<Vec<&Package>>();
+
+    packages.sort_unstable_by(|a, b| {
+        a.name
+            .cmp(&b.name)
+            .then(a.architecture.cmp(&b.architecture))
+    });
+
+    //////////////////////////////////////////////
+
+    let mut graph: String = String::new();
+
+    match graph_format {
+        "dot" => {
+            graph.push_str("digraph \"debstrap\" {\n");
+            graph.push_str("    rankdir=\"LR\";\n");
+            graph.push_str("    node [shape=\"box\"];\n\n");
+
+            for package in &packages {
+                let mut attributes: String = format!(
+                    "label=\"{}\\n{}\\n{}\"",
+                    escape_dot_string(&format_package_name(package, architecture_config)),
+                    escape_dot_string(&package.version),
+                    escape_dot_string(&package.priority)
+                );
+
+                if package.is_essential == true {
+                    attributes.push_str(", style=\"bold\"");
+                };
+
+                graph.push_str(&format!(
+                    "    \"{}\" [{attributes}];\n",
+                    escape_dot_string(&format_package_name(package, architecture_config))
+                ));
+            }
+
+            for virtual_package in &virtual_packages {
+                graph.push_str(&format!(
+                    "    \"{}\" [shape=\"ellipse\", style=\"dashed\"];\n",
+                    escape_dot_string(virtual_package)
+                ));
+            }
+
+            graph.push_str("\n");
+
+            for edge in &edges {
+                let style: &str = match &edge.kind as &str {
+                    "Pre-Depends" => "bold",
+                    "Recommends" => "dashed",
+                    "Provides" => "dotted",
+                    _ => "solid",
+                };
+
+                graph.push_str(&format!(
+                    "    \"{}\" -> \"{}\" [label=\"{}\", tooltip=\"{}\", style=\"{style}\"];\n",
+                    escape_dot_string(&edge.from),
+                    escape_dot_string(&edge.to),
+                    edge.kind,
+                    escape_dot_string(&edge.relation)
+                ));
+            }
+
+            graph.push_str("}\n");
+        }
+        "json" => {
+            let mut node_entries: Vec<String> = Vec::new();
+
+            for package in &packages {
+                node_entries.push(format!(
+                    "    {{\"id\": \"{}\", \"version\": \"{}\", \"architecture\": \"{}\", \"priority\": \"{}\", \"essential\": {}, \"virtual\": false}}",
+                    escape_json_string(&format_package_name(package, architecture_config)),
+                    escape_json_string(&package.version),
+                    escape_json_string(&package.architecture),
+                    escape_json_string(&package.priority),
+                    package.is_essential,
+                ));
+            }
+
+            for virtual_package in &virtual_packages {
+                node_entries.push(format!(
+                    "    {{\"id\": \"{}\", \"virtual\": true}}",
+                    escape_json_string(virtual_package)
+                ));
+            }
+
+            let mut edge_entries: Vec<String> = Vec::new();
+
+            for edge in &edges {
+                edge_entries.push(format!(
+                    "    {{\"from\": \"{}\", \"to\": \"{}\", \"type\": \"{}\", \"relation\": \"{}\"}}",
+                    escape_json_string(&edge.from),
+                    escape_json_string(&edge.to),
+                    escape_json_string(&edge.kind),
+                    escape_json_string(&edge.relation),
+                ));
+            }
+
+            graph = format!(
+                "{{\n  \"nodes\": [\n{}\n  ],\n  \"edges\": [\n{}\n  ]\n}}\n",
+                node_entries.join(",\n"),
+                edge_entries.join(",\n")
+            );
+        }
+        _ => {
+            print_message(
+                "error",
+                &format!("unrecognized graph format: \"{graph_format}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    if create_file(output_file, &graph, None, None, &message_config).is_err() == true {
+        return Err(());
+    };
+
+    return Ok(());
+}
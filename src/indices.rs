@@ -1,7 +1,10 @@
+use crate::deb822::*;
 use crate::functions::*;
 use crate::sources::*;
 
 use std::collections::HashMap;
+use std::io::BufRead;
+use std::path::Path;
 
 #[derive(Debug, Default, Clone)]
 pub struct Release {
@@ -15,6 +18,8 @@ pub struct Release {
     pub date: String,
     pub valid_until: String,
     pub description: String,
+    pub not_automatic: bool,
+    pub but_automatic_upgrades: bool,
     pub sha256_hashes: HashMap<String, (String, u64)>,
     pub md5_hashes: HashMap<String, (String, u64)>,
 }
@@ -23,149 +28,158 @@ pub fn parse_release_file(
     release_file: &str,
     message_config: &MessageConfig,
 ) -> Result<Release, ()> {
-    let release_entry: Release;
+    let paragraph: Paragraph;
 
     match std::fs::read_to_string(release_file) {
         Ok(result) => {
-            let mut origin: String = String::new();
-            let mut label: String = String::new();
-            let mut version: String = String::new();
-            let mut suite: String = String::new();
-            let mut code_name: String = String::new();
-            let mut architectures: Vec<String> = Vec::new();
-            let mut components: Vec<String> = Vec::new();
-            let mut date: String = String::new();
-            let mut valid_until: String = String::new();
-            let mut description: String = String::new();
-
-            for line in result.lines() {
-                match &line as &str {
-                    _ if line.starts_with("Origin: ") => {
-                        origin = line.replacen("Origin: ", "", 1);
-                    }
-                    _ if line.starts_with("Label: ") => {
-                        label = line.replacen("Label: ", "", 1);
-                    }
-                    _ if line.starts_with("Version: ") => {
-                        version = line.replacen("Version: ", "", 1);
-                    }
-                    _ if line.starts_with("Suite: ") => {
-                        suite = line.replacen("Suite: ", "", 1);
-                    }
-                    _ if line.starts_with("Codename: ") => {
-                        code_name = line.replacen("Codename: ", "", 1);
-                    }
-                    _ if line.starts_with("Architectures: ") => {
-                        architectures = parse_list_of_values("Architectures: ", &line);
-                    }
-                    _ if line.starts_with("Components: ") => {
-                        components = parse_list_of_values("Components: ", &line);
-                    }
-                    _ if line.starts_with("Date: ") => {
-                        date = line.replacen("Date: ", "", 1);
-                    }
-                    _ if line.starts_with("Valid-Until: ") => {
-                        valid_until = line.replacen("Valid-Until: ", "", 1);
-                    }
-                    _ if line.starts_with("Description: ") => {
-                        description = line.replacen("Description: ", "", 1);
-                    }
-                    _ => {}
-                };
-            }
+            let (mut paragraphs, errors) = parse_deb822_paragraphs(&result);
+
+            if errors.len() != 0 {
+                for error in &errors {
+                    print_message(
+                        "error",
+                        &format!(
+                            "invalid Release file: {}",
+                            format_deb822_error(release_file, error)
+                        ),
+                        &message_config,
+                    );
+                }
+                return Err(());
+            };
 
-            //////////////////////////////////////
+            if paragraphs.len() == 0 {
+                print_message(
+                    "error",
+                    &format!("empty Release file: \"{release_file}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
 
-            let mut sha256_hashes: HashMap<String, (String, u64)> = HashMap::new();
+            paragraph = paragraphs.remove(0);
+        }
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read Release file: \"{release_file}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
 
-            let mut continue_from_next: bool = false;
+    let get_value = |field: &str| -> String {
+        return String::from(paragraph.get(field).unwrap_or_default());
+    };
 
-            for line in result.lines() {
-                if line.starts_with("SHA256:") == true {
-                    continue_from_next = true;
-                    continue;
-                };
+    //////////////////////////////////////
 
-                if continue_from_next == true {
-                    if line.starts_with(" ") == true {
-                        let split_string: Vec<String> = line
-                            .split_whitespace()
-                            .map(|element| String::from(element.trim()))
-                            .collect::<Vec<String>>();
+    // e.g. " 0123abcd 1234 main/binary-amd64/Packages"
+    let mut hashes: Vec<HashMap<String, (String, u64)>> = Vec::new();
 
-                        sha256_hashes.insert(
-                            split_string[2].clone(),
-                            (split_string[0].clone(), split_string[1].parse().unwrap()),
-                        );
-                    } else {
-                        break;
-                    };
-                };
-            }
+    for field in ["SHA256", "MD5Sum"] {
+        let mut parsed_hashes: HashMap<String, (String, u64)> = HashMap::new();
 
-            //////////////////////////////////////
+        for (line_number, line) in paragraph.get_lines(field) {
+            let split_line: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
 
-            let mut md5_hashes: HashMap<String, (String, u64)> = HashMap::new();
+            match (
+                split_line.len(),
+                split_line.get(1).map(|size| size.parse::<u64>()),
+            ) {
+                (3, Some(Ok(size))) => {
+                    parsed_hashes.insert(
+                        String::from(split_line[2]),
+                        (String::from(split_line[0]), size),
+                    );
+                }
+                _ => {
+                    print_message(
+                        "error",
+                        &format!(
+                            "invalid Release file: {}",
+                            format_deb822_error(
+                                release_file,
+                                &Deb822Error {
+                                    line_number: line_number,
+                                    message: format!("invalid checksum: \"{}\"", line.trim()),
+                                }
+                            )
+                        ),
+                        &message_config,
+                    );
+                    return Err(());
+                }
+            };
+        }
 
-            let mut continue_from_next: bool = false;
+        hashes.push(parsed_hashes);
+    }
 
-            for line in result.lines() {
-                if line.starts_with("MD5Sum:") == true {
-                    continue_from_next = true;
-                    continue;
-                };
+    //////////////////////////////////////
+
+    let md5_hashes: HashMap<String, (String, u64)> = hashes.pop().unwrap_or_default();
+    let sha256_hashes: HashMap<String, (String, u64)> = hashes.pop().unwrap_or_default();
+
+    return Ok(Release {
+        origin: get_value("Origin"),
+        label: get_value("Label"),
+        version: get_value("Version"),
+        suite: get_value("Suite"),
+        code_name: get_value("Codename"),
+        architectures: parse_list_of_values("", &get_value("Architectures")),
+        components: parse_list_of_values("", &get_value("Components")),
+        date: get_value("Date"),
+        valid_until: get_value("Valid-Until"),
+        description: get_value("Description"),
+        not_automatic: get_value("NotAutomatic") == "yes",
+        but_automatic_upgrades: get_value("ButAutomaticUpgrades") == "yes",
+        sha256_hashes: sha256_hashes,
+        md5_hashes: md5_hashes,
+    });
+}
 
-                if continue_from_next == true {
-                    if line.starts_with(" ") == true {
-                        let split_string: Vec<String> = line
-                            .split_whitespace()
-                            .map(|element| String::from(element.trim()))
-                            .collect::<Vec<String>>();
+////////////////////////////////////////////////////////////////////////////////////////////////////
 
-                        md5_hashes.insert(
-                            split_string[2].clone(),
-                            (split_string[0].clone(), split_string[1].parse().unwrap()),
-                        );
-                    } else {
-                        break;
-                    };
-                };
-            }
+// the path of a package list relative to the suite, udebs are listed in a separate package list
+// for the debian-installer.
+pub fn get_package_list_path(component: &str, architecture: &str, use_udebs: bool) -> String {
+    match use_udebs {
+        true => return format!("{component}/debian-installer/binary-{architecture}/Packages"),
+        false => return format!("{component}/binary-{architecture}/Packages"),
+    };
+}
 
-            //////////////////////////////////////
-
-            release_entry = Release {
-                origin: origin,
-                label: label,
-                version: version,
-                suite: suite,
-                code_name: code_name,
-                architectures: architectures,
-                components: components,
-                date: date,
-                valid_until: valid_until,
-                description: description,
-                sha256_hashes: sha256_hashes,
-                md5_hashes: md5_hashes,
-            };
+// the paths of the Contents index of a component relative to the suite, in order of preference.
+// Debian splits the index by component, while Ubuntu has a single index for the whole suite.
+pub fn get_contents_index_paths(
+    component: &str,
+    architecture: &str,
+    use_udebs: bool,
+) -> Vec<String> {
+    match use_udebs {
+        true => {
+            return Vec::from([
+                format!("{component}/Contents-udeb-{architecture}"),
+                format!("Contents-udeb-{architecture}"),
+            ]);
         }
-        Err(..) => {
-            print_message(
-                "error",
-                &format!("failed to read Release file: \"{release_file}\""),
-                &message_config,
-            );
-            return Err(());
+        false => {
+            return Vec::from([
+                format!("{component}/Contents-{architecture}"),
+                format!("Contents-{architecture}"),
+            ]);
         }
     };
-
-    return Ok(release_entry);
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 pub fn download_indices(
     sources_list: &Vec<SourcesEntry>,
+    use_udebs: bool,
+    download_contents: bool,
     output_directory: &str,
     message_config: &MessageConfig,
 ) -> Result<(), ()> {
@@ -179,6 +193,10 @@ pub fn download_indices(
                 for _component in &entry.components {
                     for _architecture in &entry.architectures {
                         total_amount_to_download += 1;
+
+                        if download_contents == true {
+                            total_amount_to_download += 1;
+                        };
                     }
                 }
             }
@@ -265,22 +283,38 @@ pub fn download_indices(
                     }
                 };
 
+                let release: Release;
+
+                match parse_release_file(
+                    &format!("{output_directory}/{release_file_name}"),
+                    &message_config,
+                ) {
+                    Ok(result) => release = result,
+                    Err(..) => return Err(()),
+                };
+
                 //////////////////////////////////
 
                 for component in &entry.components {
                     for architecture in &entry.architectures {
                         counter += 1;
 
+                        let package_list_path: String =
+                            get_package_list_path(&component, &architecture, use_udebs);
+
                         println!(
-                            "{} {path} {suite}/{component} {architecture} Packages",
+                            "{} {path} {suite}/{} {architecture} Packages",
                             space_and_truncate_string(
                                 &format!("({counter}/{total_amount_to_download}):"),
                                 counter_spacing,
                             ),
+                            package_list_path
+                                .trim_end_matches(&format!("/binary-{architecture}/Packages")),
                         );
 
                         let package_list_parent_path: String = format!(
-                            "{scheme}{path}/dists/{suite}/{component}/binary-{architecture}"
+                            "{scheme}{path}/dists/{suite}/{}",
+                            package_list_path.trim_end_matches("/Packages")
                         );
 
                         let potential_file_names: Vec<String> = Vec::from([
@@ -291,10 +325,8 @@ pub fn download_indices(
                             String::from("Packages"),
                         ]);
 
-                        let package_list_file_name: String = format!(
-                            "{path}_dists_{suite}_{component}_binary-{architecture}_Packages"
-                        )
-                        .replace("/", "_");
+                        let package_list_file_name: String =
+                            format!("{path}/dists/{suite}/{package_list_path}").replace("/", "_");
 
                         let mut did_package_list_download: bool = false;
 
@@ -350,86 +382,101 @@ pub fn download_indices(
 
                         //////////////////////////
 
-                        match parse_release_file(
-                            &format!("{output_directory}/{release_file_name}"),
-                            &message_config,
-                        ) {
-                            Ok(result) => {
-                                let package_list_file_location: String =
-                                    format!("{output_directory}/{package_list_file_name}");
-
-                                if result.sha256_hashes.len() != 0 {
-                                    match result
-                                        .sha256_hashes
-                                        .get(&format!("{component}/binary-{architecture}/Packages"))
+                        let package_list_file_location: String =
+                            format!("{output_directory}/{package_list_file_name}");
+
+                        if release.sha256_hashes.len() != 0 {
+                            match release.sha256_hashes.get(&package_list_path) {
+                                Some((hash, size)) => {
+                                    if verify_file_checksum(
+                                        "sha256",
+                                        &package_list_file_location,
+                                        &hash,
+                                        &size,
+                                        &message_config,
+                                    )
+                                    .is_err()
+                                        == true
                                     {
-                                        Some((hash, size)) => {
-                                            if verify_file_checksum(
-                                                "sha256",
-                                                &package_list_file_location,
-                                                &hash,
-                                                &size,
-                                                &message_config,
-                                            )
-                                            .is_err()
-                                                == true
-                                            {
-                                                return Err(());
-                                            };
-                                        }
-                                        None => {
-                                            print_message(
-                                                "error",
-                                                &format!("failed to find checksum for file: \"{package_list_file_name}\""),
-                                                &message_config,
-                                            );
-                                            return Err(());
-                                        }
+                                        return Err(());
                                     };
-                                } else if result.md5_hashes.len() != 0 {
+                                }
+                                None => {
                                     print_message(
-                                        "warning",
-                                        "falling back to using md5 checksum.",
+                                        "error",
+                                        &format!("failed to find checksum for file: \"{package_list_file_name}\""),
                                         &message_config,
                                     );
-
-                                    match result
-                                        .md5_hashes
-                                        .get(&format!("{component}/binary-{architecture}/Packages"))
+                                    return Err(());
+                                }
+                            };
+                        } else if release.md5_hashes.len() != 0 {
+                            print_message(
+                                "warning",
+                                "falling back to using md5 checksum.",
+                                &message_config,
+                            );
+
+                            match release.md5_hashes.get(&package_list_path) {
+                                Some((hash, size)) => {
+                                    if verify_file_checksum(
+                                        "md5",
+                                        &package_list_file_location,
+                                        &hash,
+                                        &size,
+                                        &message_config,
+                                    )
+                                    .is_err()
+                                        == true
                                     {
-                                        Some((hash, size)) => {
-                                            if verify_file_checksum(
-                                                "md5",
-                                                &package_list_file_location,
-                                                &hash,
-                                                &size,
-                                                &message_config,
-                                            )
-                                            .is_err()
-                                                == true
-                                            {
-                                                return Err(());
-                                            };
-                                        }
-                                        None => {
-                                            print_message(
-                                                "error",
-                                                &format!("failed to find checksum for file: \"{package_list_file_name}\""),
-                                                &message_config,
-                                            );
-                                            return Err(());
-                                        }
+                                        return Err(());
                                     };
-                                } else {
+                                }
+                                None => {
                                     print_message(
                                         "error",
-                                        &format!("failed to find any checksums in file: \"{output_directory}/{release_file_name}\""),
+                                        &format!("failed to find checksum for file: \"{package_list_file_name}\""),
                                         &message_config,
                                     );
                                     return Err(());
-                                };
-                            }
-                            Err(..) => return Err(()),
+                                }
+                            };
+                        } else {
+                            print_message(
+                                "error",
+                                &format!("failed to find any checksums in file: \"{output_directory}/{release_file_name}\""),
+                                &message_config,
+                            );
+                            return Err(());
+                        };
+
+                        //////////////////////////
+
+                        if download_contents == true {
+                            counter += 1;
+
+                            println!(
+                                "{} {path} {suite}/{component} {architecture} Contents",
+                                space_and_truncate_string(
+                                    &format!("({counter}/{total_amount_to_download}):"),
+                                    counter_spacing,
+                                ),
+                            );
+
+                            if download_contents_index(
+                                &scheme,
+                                &path,
+                                &suite,
+                                &get_contents_index_paths(&component, &architecture, use_udebs),
+                                &release,
+                                &output_directory,
+                                &message_config,
+                            )
+                            .is_err()
+                                == true
+                            {
+                                return Err(());
+                            };
                         };
                     }
                 }
@@ -439,3 +486,369 @@ pub fn download_indices(
 
     return Ok(());
 }
+
+// the Contents index is chosen by its checksum in the Release file, as not every archive provides
+// one for each component. a missing Contents index is only a warning, as it is optional.
+fn download_contents_index(
+    scheme: &str,
+    path: &str,
+    suite: &str,
+    contents_index_paths: &Vec<String>,
+    release: &Release,
+    output_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    for contents_index_path in contents_index_paths {
+        let contents_index_file_name: String =
+            format!("{path}/dists/{suite}/{contents_index_path}").replace("/", "_");
+
+        // the Contents index of the whole suite is shared by all of its components.
+        if Path::new(&format!("{output_directory}/{contents_index_file_name}")).exists() == true {
+            return Ok(());
+        };
+
+        for extension in [".gz", ".xz", ""] {
+            let remote_path: String = format!("{contents_index_path}{extension}");
+
+            let (hash_type, (hash, size)) = match (
+                release.sha256_hashes.get(&remote_path),
+                release.md5_hashes.get(&remote_path),
+            ) {
+                (Some(result), _) => ("sha256", result),
+                (None, Some(result)) => ("md5", result),
+                (None, None) => continue,
+            };
+
+            let downloaded_file_name: String = String::from(
+                Path::new(&remote_path)
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy(),
+            );
+
+            if download_file(
+                &format!("{scheme}{path}/dists/{suite}/{remote_path}"),
+                &output_directory,
+                &message_config,
+            )
+            .is_err()
+                == true
+                || verify_file_checksum(
+                    hash_type,
+                    &format!("{output_directory}/{downloaded_file_name}"),
+                    &hash,
+                    &size,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                || decompress_file(
+                    &format!("{output_directory}/{downloaded_file_name}"),
+                    &message_config,
+                )
+                .is_err()
+                    == true
+            {
+                return Err(());
+            };
+
+            if std::fs::rename(
+                format!("{output_directory}/{downloaded_file_name}").trim_end_matches(extension),
+                format!("{output_directory}/{contents_index_file_name}"),
+            )
+            .is_err()
+                == true
+            {
+                print_message(
+                    "error",
+                    &format!("failed to rename file: \"{downloaded_file_name}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+
+            return Ok(());
+        }
+    }
+
+    print_message(
+        "warning",
+        &format!("failed to find Contents index in: \"{path}/dists/{suite}\""),
+        &message_config,
+    );
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// patterns without wildcards are compared directly, as a Contents index has millions of lines.
+pub fn does_contents_path_match(file_path: &str, pattern: &str) -> bool {
+    match pattern.contains(['*', '?']) {
+        true => return does_value_match_pattern(file_path, pattern),
+        false => return file_path == pattern,
+    };
+}
+
+// a line of a Contents index is the path of a file without the leading '/', followed by a comma
+// separated list of the packages that ship it as "[component/]section/package".
+pub fn search_contents_index(
+    contents_index_file: &str,
+    patterns: &Vec<String>,
+    message_config: &MessageConfig,
+) -> Result<Vec<(String, String)>, ()> {
+    let mut output: Vec<(String, String)> = Vec::new();
+
+    let file: std::fs::File;
+
+    match std::fs::File::open(contents_index_file) {
+        Ok(result) => file = result,
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read Contents index: \"{contents_index_file}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    for line in std::io::BufReader::new(file).lines() {
+        let line: String = match line {
+            Ok(result) => result,
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("failed to read Contents index: \"{contents_index_file}\""),
+                    &message_config,
+                );
+                return Err(());
+            }
+        };
+
+        let (file_path, locations) = match line.trim_end().rsplit_once(char::is_whitespace) {
+            Some((file_path, locations)) => (file_path.trim_end(), locations),
+            None => continue,
+        };
+
+        if patterns
+            .iter()
+            .any(|pattern| does_contents_path_match(file_path, pattern))
+            == false
+        {
+            continue;
+        };
+
+        for location in locations.split(',') {
+            match location.rsplit_once('/') {
+                Some((_, package_name)) => {
+                    output.push((String::from(file_path), String::from(package_name)));
+                }
+                None => {}
+            };
+        }
+    }
+
+    return Ok(output);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn download_source_indices(
+    sources_list: &Vec<SourcesEntry>,
+    output_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let mut total_amount_to_download: u64 = 0;
+
+    for entry in sources_list {
+        total_amount_to_download +=
+            (entry.uris.len() * entry.suites.len() * entry.components.len()) as u64;
+    }
+
+    let counter_spacing: u16;
+
+    match total_amount_to_download {
+        length if length < 10 => {
+            counter_spacing = 6;
+        }
+        length if length < 100 => {
+            counter_spacing = 8;
+        }
+        length if length < 1000 => {
+            counter_spacing = 10;
+        }
+        length if length < 10000 => {
+            counter_spacing = 12;
+        }
+        length if length < 100000 => {
+            counter_spacing = 14;
+        }
+        _ => {
+            print_message("error", "invalid size.", &message_config);
+
+            return Err(());
+        }
+    };
+
+    let mut counter: u64 = 0;
+
+    for entry in sources_list {
+        for (scheme, path) in &entry.uris {
+            for suite in &entry.suites {
+                // the Release file was already downloaded alongside the package lists.
+                let release_file_name: String =
+                    format!("{path}_dists_{suite}_Release").replace("/", "_");
+
+                let release: Release;
+
+                match parse_release_file(
+                    &format!("{output_directory}/{release_file_name}"),
+                    &message_config,
+                ) {
+                    Ok(result) => release = result,
+                    Err(..) => return Err(()),
+                };
+
+                for component in &entry.components {
+                    counter += 1;
+
+                    println!(
+                        "{} {path} {suite}/{component} Sources",
+                        space_and_truncate_string(
+                            &format!("({counter}/{total_amount_to_download}):"),
+                            counter_spacing,
+                        ),
+                    );
+
+                    let source_list_parent_path: String =
+                        format!("{scheme}{path}/dists/{suite}/{component}/source");
+
+                    let potential_file_names: Vec<String> = Vec::from([
+                        String::from("Sources.xz"),
+                        String::from("Sources.gz"),
+                        String::from("Sources.bz2"),
+                        String::from("Sources.lzma"),
+                        String::from("Sources"),
+                    ]);
+
+                    let source_list_file_name: String =
+                        format!("{path}_dists_{suite}_{component}_source_Sources")
+                            .replace("/", "_");
+
+                    let mut did_source_list_download: bool = false;
+
+                    for file_name in potential_file_names {
+                        let source_list_uri: String =
+                            format!("{source_list_parent_path}/{file_name}");
+
+                        if does_network_resource_exist(&source_list_uri) == true {
+                            if download_file(&source_list_uri, &output_directory, &message_config)
+                                .is_err()
+                                == true
+                            {
+                                return Err(());
+                            };
+
+                            did_source_list_download = true;
+
+                            if decompress_file(
+                                &format!("{output_directory}/{file_name}"),
+                                &message_config,
+                            )
+                            .is_err()
+                                == true
+                            {
+                                return Err(());
+                            };
+
+                            if std::fs::rename(
+                                format!("{output_directory}/Sources"),
+                                format!("{output_directory}/{source_list_file_name}"),
+                            )
+                            .is_err()
+                                == true
+                            {
+                                print_message(
+                                    "error",
+                                    "failed to rename file: \"Sources\"",
+                                    &message_config,
+                                );
+                                return Err(());
+                            };
+
+                            break;
+                        };
+                    }
+
+                    if did_source_list_download == false {
+                        print_message("error", "failed to find source list.", &message_config);
+                        return Err(());
+                    };
+
+                    //////////////////////////////
+
+                    let source_list_file_location: String =
+                        format!("{output_directory}/{source_list_file_name}");
+
+                    let hash_type: &str;
+                    let checksum: Option<&(String, u64)>;
+
+                    if release.sha256_hashes.len() != 0 {
+                        hash_type = "sha256";
+                        checksum = release
+                            .sha256_hashes
+                            .get(&format!("{component}/source/Sources"));
+                    } else if release.md5_hashes.len() != 0 {
+                        print_message(
+                            "warning",
+                            "falling back to using md5 checksum.",
+                            &message_config,
+                        );
+
+                        hash_type = "md5";
+                        checksum = release
+                            .md5_hashes
+                            .get(&format!("{component}/source/Sources"));
+                    } else {
+                        print_message(
+                            "error",
+                            &format!("failed to find any checksums in file: \"{output_directory}/{release_file_name}\""),
+                            &message_config,
+                        );
+                        return Err(());
+                    };
+
+                    match checksum {
+                        Some((hash, size)) => {
+                            if verify_file_checksum(
+                                hash_type,
+                                &source_list_file_location,
+                                &hash,
+                                &size,
+                                &message_config,
+                            )
+                            .is_err()
+                                == true
+                            {
+                                return Err(());
+                            };
+                        }
+                        None => {
+                            print_message(
+                                "error",
+                                &format!(
+                                    "failed to find checksum for file: \"{source_list_file_name}\""
+                                ),
+                                &message_config,
+                            );
+                            return Err(());
+                        }
+                    };
+                }
+            }
+        }
+    }
+
+    return Ok(());
+}
@@ -1,16 +1,39 @@
 use algorithms::*;
 pub mod algorithms;
+use cache::*;
+pub mod cache;
+use copyright::*;
+pub mod copyright;
+use database::*;
+pub mod database;
+use deb822::*;
+pub mod deb822;
 use defaults::*;
 pub mod defaults;
+use edsp::*;
+pub mod edsp;
 use functions::*;
 pub mod functions;
+use graph::*;
+pub mod graph;
 use indices::*;
 pub mod indices;
+use manifest::*;
+pub mod manifest;
+use output::*;
+pub mod output;
 use package::*;
 pub mod package;
+use preferences::*;
+pub mod preferences;
+use solver::*;
+pub mod solver;
+use source_package::*;
+pub mod source_package;
 use sources::*;
 pub mod sources;
 
+use byte_unit::Byte;
 use cmd_lib::{run_cmd, run_fun};
 use rand::distr::Alphanumeric;
 use rand::RngExt;
@@ -250,17 +273,34 @@ See debstrap(8) for more information."
     let mut chosen_only_action_then_exit: String = String::new();
     let mut chosen_actions_to_skip: Vec<String> = Vec::new();
     let mut chosen_output_location: String = String::new();
+    let mut chosen_cache_location: String = String::new();
     let mut chosen_output_format: String = String::new();
+    let mut chosen_compression: String = String::new();
+    let mut chosen_image_headroom: String = String::new();
+    let mut chosen_oci_layers: String = String::new();
+    let mut chosen_manifest_location: String = String::new();
+    let mut chosen_manifest_format: String = String::new();
+    let mut chosen_license_report_location: String = String::new();
+    let mut chosen_graph_location: String = String::new();
+    let mut chosen_graph_format: String = String::new();
+    let mut download_sources: bool = false;
     let mut chosen_sources_location: String = String::new();
     let mut chosen_uris: Vec<String> = Vec::new();
     let mut chosen_suites: Vec<String> = Vec::new();
     let mut chosen_components: Vec<String> = Vec::new();
     let mut chosen_architectures: Vec<String> = Vec::new();
     let mut chosen_variant: String = String::new();
+    let mut use_udebs: bool = false;
+    let mut chosen_build_depends: String = String::new();
+    let mut chosen_build_profiles: Vec<String> = Vec::new();
     let mut custom_package_set: Vec<String> = Vec::new();
     let mut chosen_packages_to_include: Vec<String> = Vec::new();
     let mut chosen_packages_to_exclude: Vec<String> = Vec::new();
     let mut chosen_packages_to_prohibit: Vec<String> = Vec::new();
+    let mut chosen_provider_preferences: Vec<String> = Vec::new();
+    let mut chosen_preferences_location: String = String::new();
+    let mut install_preferences: bool = false;
+    let mut chosen_phased_updates: String = String::new();
     let mut chosen_resolver: String = String::new();
     let mut consider_recommends: Option<bool> = None;
     let mut chosen_extractor: String = String::new();
@@ -276,6 +316,9 @@ See debstrap(8) for more information."
     let mut target_hooks: Vec<String> = Vec::new();
     let mut done_hooks: Vec<String> = Vec::new();
     let mut packages_to_print_then_exit: Vec<String> = Vec::new();
+    let mut files_to_find_then_exit: Vec<String> = Vec::new();
+    let mut packages_to_explain: Vec<String> = Vec::new();
+    let mut packages_to_explain_absence_of: Vec<String> = Vec::new();
     let mut discard_output_on_exit: bool = false;
     let mut assume_user_input: Option<String> = None;
 
@@ -294,6 +337,9 @@ See debstrap(8) for more information."
             _ if argument.starts_with("--skip=") => {
                 chosen_actions_to_skip.extend(parse_list_of_values("--skip=", &argument));
             }
+            _ if argument.starts_with("--cache=") => {
+                chosen_cache_location = String::from(argument.replacen("--cache=", "", 1).trim());
+            }
             _ if argument.starts_with("-o=") => {
                 chosen_output_location = String::from(argument.replacen("-o=", "", 1).trim());
             }
@@ -306,6 +352,39 @@ See debstrap(8) for more information."
             _ if argument.starts_with("--format=") => {
                 chosen_output_format = String::from(argument.replacen("--format=", "", 1).trim());
             }
+            _ if argument.starts_with("--compression=") => {
+                chosen_compression =
+                    String::from(argument.replacen("--compression=", "", 1).trim());
+            }
+            _ if argument.starts_with("--image-headroom=") => {
+                chosen_image_headroom =
+                    String::from(argument.replacen("--image-headroom=", "", 1).trim());
+            }
+            _ if argument.starts_with("--manifest=") => {
+                chosen_manifest_location =
+                    String::from(argument.replacen("--manifest=", "", 1).trim());
+            }
+            _ if argument.starts_with("--manifest-format=") => {
+                chosen_manifest_format =
+                    String::from(argument.replacen("--manifest-format=", "", 1).trim());
+            }
+            _ if argument.starts_with("--graph=") => {
+                chosen_graph_location = String::from(argument.replacen("--graph=", "", 1).trim());
+            }
+            _ if argument.starts_with("--graph-format=") => {
+                chosen_graph_format =
+                    String::from(argument.replacen("--graph-format=", "", 1).trim());
+            }
+            _ if argument.starts_with("--license-report=") => {
+                chosen_license_report_location =
+                    String::from(argument.replacen("--license-report=", "", 1).trim());
+            }
+            "--download-sources" => {
+                download_sources = true;
+            }
+            _ if argument.starts_with("--oci-layers=") => {
+                chosen_oci_layers = String::from(argument.replacen("--oci-layers=", "", 1).trim());
+            }
             _ if argument.starts_with("-s=") => {
                 chosen_sources_location = String::from(argument.replacen("-s=", "", 1).trim());
             }
@@ -365,17 +444,27 @@ See debstrap(8) for more information."
             _ if argument.starts_with("--variant=") => {
                 chosen_variant = String::from(argument.replacen("--variant=", "", 1).trim());
             }
+            "--udeb" => {
+                use_udebs = true;
+            }
+            _ if argument.starts_with("--build-depends=") => {
+                chosen_build_depends =
+                    String::from(argument.replacen("--build-depends=", "", 1).trim());
+            }
+            _ if argument.starts_with("--build-profiles=") => {
+                chosen_build_profiles.extend(parse_list_of_values("--build-profiles=", &argument));
+            }
             _ if argument.starts_with("-p=") => {
-                custom_package_set.extend(parse_list_of_values("-p=", &argument));
+                custom_package_set.extend(parse_list_of_packages("-p=", &argument));
             }
             _ if argument.starts_with("--package-set=") => {
-                custom_package_set.extend(parse_list_of_values("--package-set=", &argument));
+                custom_package_set.extend(parse_list_of_packages("--package-set=", &argument));
             }
             _ if argument.starts_with("-i=") => {
-                chosen_packages_to_include.extend(parse_list_of_values("-i=", &argument));
+                chosen_packages_to_include.extend(parse_list_of_packages("-i=", &argument));
             }
             _ if argument.starts_with("--include=") => {
-                chosen_packages_to_include.extend(parse_list_of_values("--include=", &argument));
+                chosen_packages_to_include.extend(parse_list_of_packages("--include=", &argument));
             }
             _ if argument.starts_with("-e=") => {
                 chosen_packages_to_exclude.extend(parse_list_of_values("-e=", &argument));
@@ -389,6 +478,20 @@ See debstrap(8) for more information."
             _ if argument.starts_with("--prohibit=") => {
                 chosen_packages_to_prohibit.extend(parse_list_of_values("--prohibit=", &argument));
             }
+            _ if argument.starts_with("--prefer=") => {
+                chosen_provider_preferences.extend(parse_list_of_values("--prefer=", &argument));
+            }
+            _ if argument.starts_with("--preferences=") => {
+                chosen_preferences_location =
+                    String::from(argument.replacen("--preferences=", "", 1).trim());
+            }
+            "--install-preferences" => {
+                install_preferences = true;
+            }
+            _ if argument.starts_with("--phased-updates=") => {
+                chosen_phased_updates =
+                    String::from(argument.replacen("--phased-updates=", "", 1).trim());
+            }
             _ if argument.starts_with("-R=") => {
                 chosen_resolver = String::from(argument.replacen("-R=", "", 1).trim());
             }
@@ -465,12 +568,22 @@ See debstrap(8) for more information."
                     argument.replacen("--hook-done=", "", 1).trim(),
                 ));
             }
+            _ if argument.starts_with("--why=") => {
+                packages_to_explain.extend(parse_list_of_values("--why=", &argument));
+            }
+            _ if argument.starts_with("--why-not=") => {
+                packages_to_explain_absence_of
+                    .extend(parse_list_of_values("--why-not=", &argument));
+            }
             _ if argument.starts_with("-F=") => {
                 packages_to_print_then_exit.extend(parse_list_of_values("-F=", &argument));
             }
             _ if argument.starts_with("--find=") => {
                 packages_to_print_then_exit.extend(parse_list_of_values("--find=", &argument));
             }
+            _ if argument.starts_with("--find-file=") => {
+                files_to_find_then_exit.extend(parse_list_of_values("--find-file=", &argument));
+            }
             "-D" | "--discard" | "--discard-output" => {
                 discard_output_on_exit = true;
             }
@@ -612,6 +725,11 @@ See debstrap(8) for more information."
                         target_actions_to_skip.push(String::from("architecture_check"));
                     };
                 }
+                "index-cache" => {
+                    if target_actions_to_skip.contains(&String::from("index_cache")) == false {
+                        target_actions_to_skip.push(String::from("index_cache"));
+                    };
+                }
                 "output-directory-check" => {
                     if target_actions_to_skip.contains(&String::from("output_directory_check"))
                         == false
@@ -639,6 +757,45 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
+    let mut cache_directory: String = String::new();
+
+    if target_actions_to_skip.contains(&String::from("index_cache")) == false {
+        if chosen_cache_location.is_empty() == false {
+            cache_directory = chosen_cache_location;
+        } else if let Ok(result) = std::env::var("DEBSTRAP_CACHE") {
+            cache_directory = result;
+        } else if let Ok(result) = std::env::var("XDG_CACHE_HOME") {
+            cache_directory = format!("{result}/debstrap");
+        } else if let Ok(result) = std::env::var("HOME") {
+            cache_directory = format!("{result}/.cache/debstrap");
+        };
+
+        if cache_directory.is_empty() == false
+            && std::fs::create_dir_all(&cache_directory).is_err() == true
+        {
+            print_message(
+                "warning",
+                &format!("failed to create cache directory: \"{cache_directory}\", not using the index cache"),
+                &message_config,
+            );
+
+            cache_directory = String::new();
+        };
+    };
+
+    let cache_directory: String = cache_directory;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{cache_directory}\"",
+            space_and_truncate_string("cache directory:", 47)
+        ),
+        &message_config,
+    );
+
+    //////////////////////////////////////////////
+
     let implied_output_format: String;
     let target_output_directory: String;
     let mut chosen_output_file_name: String = String::new();
@@ -710,11 +867,71 @@ See debstrap(8) for more information."
                 String::from(provided_path.file_name().unwrap().to_string_lossy());
 
             match &chosen_output_file_name {
+                file_name if file_name.ends_with(".cpio") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".cpio").unwrap());
+                    implied_output_format = String::from("cpio");
+                }
+                file_name if file_name.contains(".cpio.") == true => {
+                    let (base_name, compression_suffix) = file_name.rsplit_once(".cpio.").unwrap();
+
+                    let implied_compression: &str;
+
+                    match compression_suffix {
+                        "bz2" => implied_compression = "bzip2",
+                        "gz" => implied_compression = "gzip",
+                        "lz4" => implied_compression = "lz4",
+                        "lzma" => implied_compression = "lzma",
+                        "lzo" => implied_compression = "lzo",
+                        "xz" => implied_compression = "xz",
+                        "zst" => implied_compression = "zstd",
+                        _ => {
+                            print_message(
+                                "error",
+                                &format!("invalid file name: \"{chosen_output_location}\""),
+                                &message_config,
+                            );
+                            return ExitCode::from(1);
+                        }
+                    };
+
+                    if chosen_compression.is_empty() == true {
+                        chosen_compression = String::from(implied_compression);
+                    };
+
+                    chosen_output_file_name = String::from(base_name);
+                    implied_output_format = String::from("cpio");
+                }
+                file_name if file_name.ends_with(".oci.tar") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".oci.tar").unwrap());
+                    implied_output_format = String::from("oci-archive");
+                }
+                file_name if file_name.ends_with(".oci") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".oci").unwrap());
+                    implied_output_format = String::from("oci");
+                }
                 file_name if file_name.ends_with(".tar") => {
                     chosen_output_file_name =
                         String::from(chosen_output_file_name.strip_suffix(".tar").unwrap());
                     implied_output_format = String::from("tarball");
                 }
+                file_name if file_name.ends_with(".squashfs") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".squashfs").unwrap());
+                    implied_output_format = String::from("squashfs");
+                }
+                file_name if file_name.ends_with(".ext4") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".ext4").unwrap());
+                    implied_output_format = String::from("ext4");
+                }
+                file_name if file_name.ends_with(".img") => {
+                    chosen_output_file_name =
+                        String::from(chosen_output_file_name.strip_suffix(".img").unwrap());
+                    implied_output_format = String::from("img");
+                }
                 _ => {
                     print_message(
                         "error",
@@ -751,6 +968,24 @@ See debstrap(8) for more information."
         "tarball" | "tar" => {
             target_output_format = String::from("tarball");
         }
+        "squashfs" | "sqfs" => {
+            target_output_format = String::from("squashfs");
+        }
+        "ext4" => {
+            target_output_format = String::from("ext4");
+        }
+        "img" | "image" => {
+            target_output_format = String::from("img");
+        }
+        "oci" => {
+            target_output_format = String::from("oci");
+        }
+        "oci-archive" => {
+            target_output_format = String::from("oci-archive");
+        }
+        "cpio" | "initramfs" => {
+            target_output_format = String::from("cpio");
+        }
         _ => {
             print_message(
                 "error",
@@ -783,6 +1018,20 @@ See debstrap(8) for more information."
         };
     };
 
+    if only_action_then_exit == "download_packages" {
+        match &target_output_format as &str {
+            "directory" | "tarball" => {}
+            _ => {
+                print_message(
+                    "error",
+                    &format!("format: \"{target_output_format}\" does not support the action: \"download\""),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            }
+        };
+    };
+
     if target_actions_to_skip.contains(&String::from("output_directory_check")) == true
         || target_output_format != "directory"
         || discard_output_on_exit == true
@@ -818,174 +1067,584 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
-    let using_sources_file: bool;
-
-    if chosen_sources_location.is_empty() == true {
-        match std::env::var("DEBSTRAP_SOURCES") {
-            Ok(result) => {
-                chosen_sources_location = String::from(result);
-                using_sources_file = true;
-            }
-            Err(..) => {
-                using_sources_file = false;
-            }
-        };
-    } else {
-        using_sources_file = true;
-    };
-
-    let mut sources_list: Vec<SourcesEntry> = Vec::new();
+    let target_compression: String;
 
-    match using_sources_file {
-        true => {
-            if chosen_uris.len() != 0
-                || chosen_suites.len() != 0
-                || chosen_components.len() != 0
-                || chosen_architectures.len() != 0
-            {
-                print_message(
-                    "warning",
-                    "ignoring provided URI(s), suite(s), component(s), and architecture(s)",
-                    &message_config,
-                );
+    match &target_output_format as &str {
+        "squashfs" => {
+            if chosen_compression.is_empty() == true {
+                chosen_compression = String::from("xz");
             };
 
-            while chosen_sources_location.contains("//") == true {
-                chosen_sources_location = chosen_sources_location.replace("//", "/");
-            }
-
-            match Path::new(&chosen_sources_location).canonicalize() {
-                Ok(result) => {
-                    chosen_sources_location = String::from(result.to_string_lossy());
+            match &chosen_compression as &str {
+                "gzip" | "lzo" | "lz4" | "xz" | "zstd" | "lzma" => {
+                    target_compression = chosen_compression.clone();
                 }
-                Err(..) => {
+                _ => {
                     print_message(
                         "error",
-                        &format!("invalid sources location: \"{chosen_sources_location}\""),
+                        &format!("unrecognized compression: \"{chosen_compression}\""),
                         &message_config,
                     );
                     return ExitCode::from(1);
                 }
             };
 
-            if chosen_sources_location.ends_with("/") == true {
-                chosen_sources_location =
-                    String::from(chosen_sources_location.strip_suffix("/").unwrap());
-            };
-
-            let mut target_sources_files: Vec<String> = Vec::new();
-
-            if Path::new(&chosen_sources_location).is_dir() == true {
-                let mut potential_sources_files: Vec<String> =
-                    std::fs::read_dir(&chosen_sources_location)
-                        .unwrap()
-                        .map(|element| String::from(element.unwrap().path().to_string_lossy()))
-                        .collect::<Vec<String>>();
-
-                potential_sources_files.sort_unstable();
-
-                for entry in potential_sources_files {
-                    if Path::new(&entry).is_file() == true {
-                        match entry {
-                            _ if entry.ends_with(".sources") => {
-                                target_sources_files.push(entry);
-                            }
-                            _ => {
-                                print_message(
-                                    "debug",
-                                    &format!("ignoring file: \"{entry}\""),
-                                    &message_config,
-                                );
-                            }
-                        };
-                    };
-                }
-            } else if Path::new(&chosen_sources_location).is_file() == true {
-                if chosen_sources_location.ends_with(".sources") == true {
-                    target_sources_files.push(String::from(chosen_sources_location));
-                } else {
-                    print_message(
-                        "error",
-                        &format!("invalid sources file: \"{chosen_sources_location}\""),
-                        &message_config,
-                    );
-                    return ExitCode::from(1);
-                };
-            } else {
+            if which("mksquashfs").is_err() == true {
                 print_message(
                     "error",
-                    &format!("invalid sources location: \"{chosen_sources_location}\""),
+                    "mksquashfs is not available on the host.",
                     &message_config,
                 );
                 return ExitCode::from(1);
             };
+        }
+        "cpio" => {
+            if chosen_compression.is_empty() == true {
+                chosen_compression = String::from("none");
+            };
 
-            for file in &target_sources_files {
-                match parse_sources_file(file, &message_config) {
-                    Ok(result) => {
-                        sources_list.extend(result);
+            match &chosen_compression as &str {
+                "none" => {
+                    target_compression = chosen_compression.clone();
+                }
+                _ => match get_compressor(&chosen_compression) {
+                    Ok((program, _arguments, _suffix)) => {
+                        if which(&program).is_err() == true {
+                            print_message(
+                                "error",
+                                &format!("{program} is not available on the host."),
+                                &message_config,
+                            );
+                            return ExitCode::from(1);
+                        };
+
+                        target_compression = chosen_compression.clone();
                     }
                     Err(..) => {
+                        print_message(
+                            "error",
+                            &format!("unrecognized compression: \"{chosen_compression}\""),
+                            &message_config,
+                        );
                         return ExitCode::from(1);
                     }
-                };
-            }
+                },
+            };
+
+            if which("cpio").is_err() == true {
+                print_message(
+                    "error",
+                    "cpio is not available on the host.",
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
         }
-        false => {
-            let mut is_keyword_host_present: bool = false;
+        _ => {
+            if chosen_compression.is_empty() == false {
+                print_message(
+                    "error",
+                    &format!("format: \"{target_output_format}\" does not support compression."),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
 
-            for architecture in chosen_architectures.iter_mut() {
-                if architecture == &"host" {
-                    is_keyword_host_present = true;
-                    *architecture = String::new();
-                };
-            }
+            target_compression = String::new();
+        }
+    };
 
-            if target_actions_to_skip.contains(&String::from("architecture_check")) == false
-                || is_keyword_host_present == true
-            {
-                if which("arch-test").is_err() == true {
-                    print_message(
-                        "error",
-                        "arch-test is not available on the host.",
-                        &message_config,
-                    );
-                    return ExitCode::from(1);
-                };
-            };
+    let target_compression: String = target_compression;
 
-            if is_keyword_host_present == true {
-                chosen_architectures.sort_unstable();
-                chosen_architectures.dedup();
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_compression}\"",
+            space_and_truncate_string("target compression:", 47)
+        ),
+        &message_config,
+    );
 
-                while chosen_architectures.contains(&String::new()) == true {
-                    chosen_architectures.remove(0);
-                }
+    let source_date_epoch: Option<u64>;
 
-                let remaining_architectures: Vec<String> = chosen_architectures;
+    match get_source_date_epoch(&message_config) {
+        Ok(result) => source_date_epoch = result,
+        Err(..) => return ExitCode::from(1),
+    };
 
-                chosen_architectures = vec![run_fun!(uname "--machine").unwrap()];
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{:?}\"",
+            space_and_truncate_string("source date epoch:", 47),
+            source_date_epoch
+        ),
+        &message_config,
+    );
 
-                chosen_architectures.extend(
-                    String::from(run_fun!("arch-test" "-n").unwrap())
-                        .split_whitespace()
-                        .map(|element| String::from(element))
-                        .collect::<Vec<String>>(),
-                );
+    let target_image_headroom_percentage: u64;
+    let target_image_headroom_size: u64;
 
-                chosen_architectures.extend(remaining_architectures);
+    match &target_output_format as &str {
+        "ext4" | "img" => {
+            if chosen_image_headroom.is_empty() == true {
+                chosen_image_headroom = String::from("25%");
             };
 
-            match create_sources_list(
-                &chosen_uris,
-                &chosen_suites,
-                &chosen_components,
-                &chosen_architectures,
-                &message_config,
-            ) {
-                Ok(result) => {
-                    sources_list = result;
-                }
+            if chosen_image_headroom.ends_with("%") == true {
+                match chosen_image_headroom.trim_end_matches("%").parse::<u64>() {
+                    Ok(result) => {
+                        target_image_headroom_percentage = result;
+                        target_image_headroom_size = 0;
+                    }
+                    Err(..) => {
+                        print_message(
+                            "error",
+                            &format!("invalid image headroom: \"{chosen_image_headroom}\""),
+                            &message_config,
+                        );
+                        return ExitCode::from(1);
+                    }
+                };
+            } else {
+                match Byte::parse_str(&chosen_image_headroom, true) {
+                    Ok(result) => {
+                        target_image_headroom_percentage = 0;
+                        target_image_headroom_size = result.as_u64();
+                    }
+                    Err(..) => {
+                        print_message(
+                            "error",
+                            &format!("invalid image headroom: \"{chosen_image_headroom}\""),
+                            &message_config,
+                        );
+                        return ExitCode::from(1);
+                    }
+                };
+            };
+
+            if which("mke2fs").is_err() == true {
+                print_message(
+                    "error",
+                    "mke2fs is not available on the host.",
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+
+            if target_output_format == "img" && which("sfdisk").is_err() == true {
+                print_message(
+                    "error",
+                    "sfdisk is not available on the host.",
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+        }
+        _ => {
+            if chosen_image_headroom.is_empty() == false {
+                print_message(
+                    "error",
+                    &format!("format: \"{target_output_format}\" does not support image headroom."),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+
+            target_image_headroom_percentage = 0;
+            target_image_headroom_size = 0;
+        }
+    };
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_image_headroom_percentage}%\" + \"{target_image_headroom_size}\" bytes",
+            space_and_truncate_string("target image headroom:", 47)
+        ),
+        &message_config,
+    );
+
+    let target_oci_layers: String;
+
+    match &target_output_format as &str {
+        "oci" | "oci-archive" => {
+            if chosen_oci_layers.is_empty() == true {
+                chosen_oci_layers = String::from("single");
+            };
+
+            match &chosen_oci_layers as &str {
+                "single" | "split" => {
+                    target_oci_layers = chosen_oci_layers.clone();
+                }
+                _ => {
+                    print_message(
+                        "error",
+                        &format!("unrecognized OCI layers: \"{chosen_oci_layers}\""),
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                }
+            };
+
+            if which("gzip").is_err() == true {
+                print_message(
+                    "error",
+                    "gzip is not available on the host.",
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+        }
+        _ => {
+            if chosen_oci_layers.is_empty() == false {
+                print_message(
+                    "error",
+                    &format!("format: \"{target_output_format}\" does not support OCI layers."),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+
+            target_oci_layers = String::new();
+        }
+    };
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_oci_layers}\"",
+            space_and_truncate_string("target OCI layers:", 47)
+        ),
+        &message_config,
+    );
+
+    //////////////////////////////////////////////
+
+    let mut target_manifest_file: String = String::new();
+    let mut target_manifest_format: String = String::new();
+
+    if chosen_manifest_location.is_empty() == false {
+        match resolve_output_file_location(&chosen_manifest_location, &message_config) {
+            Ok(result) => target_manifest_file = result,
+            Err(..) => return ExitCode::from(1),
+        };
+
+        if chosen_manifest_format.is_empty() == true {
+            match &target_manifest_file {
+                file_name if file_name.ends_with(".spdx.json") => {
+                    chosen_manifest_format = String::from("spdx");
+                }
+                file_name if file_name.ends_with(".cdx.json") => {
+                    chosen_manifest_format = String::from("cyclonedx");
+                }
+                file_name if file_name.ends_with(".json") => {
+                    chosen_manifest_format = String::from("json");
+                }
+                _ => {
+                    chosen_manifest_format = String::from("text");
+                }
+            };
+        };
+
+        match &chosen_manifest_format as &str {
+            "text" | "json" | "spdx" => {
+                target_manifest_format = chosen_manifest_format.clone();
+            }
+            "cyclonedx" | "cdx" => {
+                target_manifest_format = String::from("cyclonedx");
+            }
+            _ => {
+                print_message(
+                    "error",
+                    &format!("unrecognized manifest format: \"{chosen_manifest_format}\""),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            }
+        };
+    } else if chosen_manifest_format.is_empty() == false {
+        print_message(
+            "error",
+            "no manifest location was provided.",
+            &message_config,
+        );
+        return ExitCode::from(1);
+    };
+
+    let target_manifest_file: String = target_manifest_file;
+    let target_manifest_format: String = target_manifest_format;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_manifest_file}\"",
+            space_and_truncate_string("target manifest file:", 47)
+        ),
+        &message_config,
+    );
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_manifest_format}\"",
+            space_and_truncate_string("target manifest format:", 47)
+        ),
+        &message_config,
+    );
+
+    let mut target_graph_file: String = String::new();
+    let mut target_graph_format: String = String::new();
+
+    if chosen_graph_location.is_empty() == false {
+        match resolve_output_file_location(&chosen_graph_location, &message_config) {
+            Ok(result) => target_graph_file = result,
+            Err(..) => return ExitCode::from(1),
+        };
+
+        if chosen_graph_format.is_empty() == true {
+            match &target_graph_file {
+                file_name if file_name.ends_with(".json") => {
+                    chosen_graph_format = String::from("json");
+                }
+                _ => {
+                    chosen_graph_format = String::from("dot");
+                }
+            };
+        };
+
+        match &chosen_graph_format as &str {
+            "dot" | "json" => {
+                target_graph_format = chosen_graph_format.clone();
+            }
+            "gv" | "graphviz" => {
+                target_graph_format = String::from("dot");
+            }
+            _ => {
+                print_message(
+                    "error",
+                    &format!("unrecognized graph format: \"{chosen_graph_format}\""),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            }
+        };
+    } else if chosen_graph_format.is_empty() == false {
+        print_message("error", "no graph location was provided.", &message_config);
+        return ExitCode::from(1);
+    };
+
+    let target_graph_file: String = target_graph_file;
+    let target_graph_format: String = target_graph_format;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_graph_file}\"",
+            space_and_truncate_string("target graph file:", 47)
+        ),
+        &message_config,
+    );
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_graph_format}\"",
+            space_and_truncate_string("target graph format:", 47)
+        ),
+        &message_config,
+    );
+
+    let mut target_license_report_file: String = String::new();
+
+    if chosen_license_report_location.is_empty() == false {
+        match resolve_output_file_location(&chosen_license_report_location, &message_config) {
+            Ok(result) => target_license_report_file = result,
+            Err(..) => return ExitCode::from(1),
+        };
+    };
+
+    let target_license_report_file: String = target_license_report_file;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_license_report_file}\"",
+            space_and_truncate_string("target license report file:", 47)
+        ),
+        &message_config,
+    );
+
+    //////////////////////////////////////////////
+
+    let using_sources_file: bool;
+
+    if chosen_sources_location.is_empty() == true {
+        match std::env::var("DEBSTRAP_SOURCES") {
+            Ok(result) => {
+                chosen_sources_location = String::from(result);
+                using_sources_file = true;
+            }
+            Err(..) => {
+                using_sources_file = false;
+            }
+        };
+    } else {
+        using_sources_file = true;
+    };
+
+    let mut sources_list: Vec<SourcesEntry> = Vec::new();
+
+    match using_sources_file {
+        true => {
+            if chosen_uris.len() != 0
+                || chosen_suites.len() != 0
+                || chosen_components.len() != 0
+                || chosen_architectures.len() != 0
+            {
+                print_message(
+                    "warning",
+                    "ignoring provided URI(s), suite(s), component(s), and architecture(s)",
+                    &message_config,
+                );
+            };
+
+            while chosen_sources_location.contains("//") == true {
+                chosen_sources_location = chosen_sources_location.replace("//", "/");
+            }
+
+            match Path::new(&chosen_sources_location).canonicalize() {
+                Ok(result) => {
+                    chosen_sources_location = String::from(result.to_string_lossy());
+                }
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("invalid sources location: \"{chosen_sources_location}\""),
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                }
+            };
+
+            if chosen_sources_location.ends_with("/") == true {
+                chosen_sources_location =
+                    String::from(chosen_sources_location.strip_suffix("/").unwrap());
+            };
+
+            let mut target_sources_files: Vec<String> = Vec::new();
+
+            if Path::new(&chosen_sources_location).is_dir() == true {
+                let mut potential_sources_files: Vec<String> =
+                    std::fs::read_dir(&chosen_sources_location)
+                        .unwrap()
+                        .map(|element| String::from(element.unwrap().path().to_string_lossy()))
+                        .collect::<Vec<String>>();
+
+                potential_sources_files.sort_unstable();
+
+                for entry in potential_sources_files {
+                    if Path::new(&entry).is_file() == true {
+                        match entry {
+                            _ if entry.ends_with(".sources") => {
+                                target_sources_files.push(entry);
+                            }
+                            _ => {
+                                print_message(
+                                    "debug",
+                                    &format!("ignoring file: \"{entry}\""),
+                                    &message_config,
+                                );
+                            }
+                        };
+                    };
+                }
+            } else if Path::new(&chosen_sources_location).is_file() == true {
+                if chosen_sources_location.ends_with(".sources") == true {
+                    target_sources_files.push(String::from(chosen_sources_location));
+                } else {
+                    print_message(
+                        "error",
+                        &format!("invalid sources file: \"{chosen_sources_location}\""),
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                };
+            } else {
+                print_message(
+                    "error",
+                    &format!("invalid sources location: \"{chosen_sources_location}\""),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            };
+
+            for file in &target_sources_files {
+                match parse_sources_file(file, &message_config) {
+                    Ok(result) => {
+                        sources_list.extend(result);
+                    }
+                    Err(..) => {
+                        return ExitCode::from(1);
+                    }
+                };
+            }
+        }
+        false => {
+            let mut is_keyword_host_present: bool = false;
+
+            for architecture in chosen_architectures.iter_mut() {
+                if architecture == &"host" {
+                    is_keyword_host_present = true;
+                    *architecture = String::new();
+                };
+            }
+
+            if target_actions_to_skip.contains(&String::from("architecture_check")) == false
+                || is_keyword_host_present == true
+            {
+                if which("arch-test").is_err() == true {
+                    print_message(
+                        "error",
+                        "arch-test is not available on the host.",
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                };
+            };
+
+            if is_keyword_host_present == true {
+                chosen_architectures.sort_unstable();
+                chosen_architectures.dedup();
+
+                while chosen_architectures.contains(&String::new()) == true {
+                    chosen_architectures.remove(0);
+                }
+
+                let remaining_architectures: Vec<String> = chosen_architectures;
+
+                chosen_architectures = vec![run_fun!(uname "--machine").unwrap()];
+
+                chosen_architectures.extend(
+                    String::from(run_fun!("arch-test" "-n").unwrap())
+                        .split_whitespace()
+                        .map(|element| String::from(element))
+                        .collect::<Vec<String>>(),
+                );
+
+                chosen_architectures.extend(remaining_architectures);
+            };
+
+            match create_sources_list(
+                &chosen_uris,
+                &chosen_suites,
+                &chosen_components,
+                &chosen_architectures,
+                &message_config,
+            ) {
+                Ok(result) => {
+                    sources_list = result;
+                }
                 Err(..) => {
                     return ExitCode::from(1);
                 }
@@ -1072,6 +1731,11 @@ See debstrap(8) for more information."
 
     let list_of_target_architectures: Vec<String> = list_of_target_architectures;
 
+    let architecture_config: ArchitectureConfig = ArchitectureConfig {
+        primary_architecture: primary_architecture.clone(),
+        list_of_architectures: list_of_target_architectures.clone(),
+    };
+
     if target_actions_to_skip.contains(&String::from("architecture_check")) == true {
         print_message("debug", "skipping architecture check.", &message_config);
     } else {
@@ -1093,6 +1757,15 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
+    if chosen_variant.is_empty() && chosen_build_depends.is_empty() == false {
+        chosen_variant = String::from("buildd");
+    };
+
+    // udebs are never essential, so the package set of the debian-installer has to be chosen.
+    if chosen_variant.is_empty() && use_udebs == true {
+        chosen_variant = String::from("custom");
+    };
+
     if chosen_variant.is_empty() {
         chosen_variant = String::from("important");
     };
@@ -1131,36 +1804,113 @@ See debstrap(8) for more information."
     print_message(
         "debug",
         &format!(
-            "{} \"{target_variant}\"",
-            space_and_truncate_string("target variant:", 47)
+            "{} \"{target_variant}\"",
+            space_and_truncate_string("target variant:", 47)
+        ),
+        &message_config,
+    );
+
+    if use_udebs == true && target_variant == "buildd" {
+        print_message(
+            "error",
+            "the variant: \"buildd\" can't be used with udebs.",
+            &message_config,
+        );
+        return ExitCode::from(1);
+    };
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{use_udebs}\"",
+            space_and_truncate_string("use udebs:", 47)
+        ),
+        &message_config,
+    );
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{}\"",
+            space_and_truncate_string("no. of packages in custom set:", 47),
+            &custom_package_set.len()
+        ),
+        &message_config,
+    );
+
+    if target_variant == "custom" {
+        if custom_package_set.len() == 0 {
+            print_message("error", "no package set was provided.", &message_config);
+            return ExitCode::from(1);
+        };
+    } else if custom_package_set.len() != 0 {
+        print_message(
+            "error",
+            "only the variant: \"custom\" can use a custom package set.",
+            &message_config,
+        );
+        return ExitCode::from(1);
+    };
+
+    //////////////////////////////////////////////
+
+    let target_build_depends: String = chosen_build_depends;
+
+    let mut target_build_depends_file: String = String::new();
+
+    if target_build_depends.is_empty() == false {
+        if target_variant != "buildd" {
+            print_message(
+                "error",
+                "only the variant: \"buildd\" can use build dependencies.",
+                &message_config,
+            );
+            return ExitCode::from(1);
+        };
+
+        if target_build_depends.ends_with(".dsc") == true {
+            match Path::new(&target_build_depends).canonicalize() {
+                Ok(result) => {
+                    target_build_depends_file = String::from(result.to_string_lossy());
+                }
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("invalid source package file: \"{target_build_depends}\""),
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                }
+            };
+        };
+    };
+
+    let target_build_depends_file: String = target_build_depends_file;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_build_depends}\"",
+            space_and_truncate_string("target build dependencies of:", 47)
         ),
         &message_config,
     );
 
+    chosen_build_profiles.sort_unstable();
+    chosen_build_profiles.dedup();
+
+    let build_profiles: Vec<String> = chosen_build_profiles;
+
     print_message(
         "debug",
         &format!(
-            "{} \"{}\"",
-            space_and_truncate_string("no. of packages in custom set:", 47),
-            &custom_package_set.len()
+            "{} {:?}",
+            space_and_truncate_string("target build profiles:", 47),
+            build_profiles
         ),
         &message_config,
     );
 
-    if target_variant == "custom" {
-        if custom_package_set.len() == 0 {
-            print_message("error", "no package set was provided.", &message_config);
-            return ExitCode::from(1);
-        };
-    } else if custom_package_set.len() != 0 {
-        print_message(
-            "error",
-            "only the variant: \"custom\" can use a custom package set.",
-            &message_config,
-        );
-        return ExitCode::from(1);
-    };
-
     //////////////////////////////////////////////
 
     let mut target_output_file_name: String = String::new();
@@ -1168,7 +1918,7 @@ See debstrap(8) for more information."
     let mut output_file_suffix: String = String::new();
 
     match &target_output_format as &str {
-        "tarball" => {
+        "tarball" | "squashfs" | "ext4" | "img" | "oci" | "oci-archive" | "cpio" => {
             match chosen_output_file_name.is_empty() {
                 true => {
                     target_output_file_name = format!(
@@ -1195,11 +1945,27 @@ See debstrap(8) for more information."
                 false => target_output_file_name = chosen_output_file_name,
             };
 
-            output_file_suffix = String::from(".tar");
+            match &target_output_format as &str {
+                "tarball" => output_file_suffix = String::from(".tar"),
+                "squashfs" => output_file_suffix = String::from(".squashfs"),
+                "ext4" => output_file_suffix = String::from(".ext4"),
+                "img" => output_file_suffix = String::from(".img"),
+                "oci" => output_file_suffix = String::from(".oci"),
+                "oci-archive" => output_file_suffix = String::from(".oci.tar"),
+                "cpio" => match get_compressor(&target_compression) {
+                    Ok((_program, _arguments, suffix)) => {
+                        output_file_suffix = format!(".cpio{suffix}");
+                    }
+                    Err(..) => output_file_suffix = String::from(".cpio"),
+                },
+                _ => {}
+            };
         }
         _ => {}
     };
 
+    let output_file_suffix: String = output_file_suffix;
+
     let target_output_file_name: String = target_output_file_name;
 
     print_message(
@@ -1211,6 +1977,40 @@ See debstrap(8) for more information."
         &message_config,
     );
 
+    let mut target_sources_directory: String = String::new();
+
+    if download_sources == true {
+        match &target_output_format as &str {
+            "directory" => {
+                target_sources_directory = format!("{target_output_directory}_sources");
+            }
+            _ => {
+                target_sources_directory =
+                    format!("{target_output_directory}/{target_output_file_name}_sources");
+            }
+        };
+
+        if Path::new(&target_sources_directory).exists() == true {
+            print_message(
+                "error",
+                &format!("source package location: \"{target_sources_directory}\" already exists."),
+                &message_config,
+            );
+            return ExitCode::from(1);
+        };
+    };
+
+    let target_sources_directory: String = target_sources_directory;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_sources_directory}\"",
+            space_and_truncate_string("target source package location:", 47)
+        ),
+        &message_config,
+    );
+
     //////////////////////////////////////////////
 
     if chosen_resolver.is_empty() == true {
@@ -1226,6 +2026,26 @@ See debstrap(8) for more information."
         "none" => {
             target_resolver = String::from("none");
         }
+        "sat" => {
+            target_resolver = String::from("sat");
+        }
+        _ if chosen_resolver.starts_with("edsp:") == true => {
+            let solver_location: String = chosen_resolver.replacen("edsp:", "", 1);
+
+            match which(&solver_location) {
+                Ok(result) => {
+                    target_resolver = format!("edsp:{}", result.to_string_lossy());
+                }
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("failed to find external solver: \"{solver_location}\""),
+                        &message_config,
+                    );
+                    return ExitCode::from(1);
+                }
+            };
+        }
         _ => {
             print_message(
                 "error",
@@ -1318,6 +2138,20 @@ See debstrap(8) for more information."
         _ => {}
     };
 
+    // udebs aren't installed in stages, so they are all extracted at once.
+    if use_udebs == true {
+        if extract_only_essentials == Some(true) {
+            print_message(
+                "error",
+                "only extracting essential packages isn't supported with udebs.",
+                &message_config,
+            );
+            return ExitCode::from(1);
+        };
+
+        extract_only_essentials = Some(false);
+    };
+
     if extract_only_essentials.is_none() == true {
         if target_variant == "essential" && chosen_packages_to_include.len() == 0 {
             extract_only_essentials = Some(false);
@@ -1382,6 +2216,10 @@ See debstrap(8) for more information."
         "no" | "false" => {
             merge_usr_directories = false;
         }
+        // the debian-installer doesn't merge the /usr directories.
+        "auto" if use_udebs == true => {
+            merge_usr_directories = false;
+        }
         "auto" => {
             match default_merge_usr_directories(&primary_suite, &target_variant) {
                 true => {
@@ -1412,7 +2250,10 @@ See debstrap(8) for more information."
         &message_config,
     );
 
-    if merge_usr_directories == false && is_split_usr_supported(&primary_suite) == false {
+    if use_udebs == false
+        && merge_usr_directories == false
+        && is_split_usr_supported(&primary_suite) == false
+    {
         print_message(
             "warning",
             "upgrading non-merged-/usr environments post-bookworm is unsupported.",
@@ -1481,17 +2322,26 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
-    match case_specific_packages(&primary_suite, &target_variant) {
-        Some(to_include) => {
-            for package in to_include {
-                chosen_packages_to_include.push(String::from(package));
+    // the case specific packages aren't built as udebs.
+    if use_udebs == false {
+        match case_specific_packages(&primary_suite, &target_variant) {
+            Some(to_include) => {
+                for package in to_include {
+                    chosen_packages_to_include.push(String::from(package));
+                }
             }
-        }
-        None => {}
+            None => {}
+        };
     };
 
     //////////////////////////////////////////////
 
+    chosen_packages_to_include = apply_package_list_restrictions(
+        &chosen_packages_to_include,
+        &primary_architecture,
+        &build_profiles,
+    );
+
     chosen_packages_to_include.sort_unstable();
     chosen_packages_to_include.dedup();
 
@@ -1538,6 +2388,99 @@ See debstrap(8) for more information."
         &message_config,
     );
 
+    let mut provider_preferences: HashMap<String, String> = HashMap::new();
+
+    for preference in &chosen_provider_preferences {
+        match preference.split_once(':') {
+            Some((virtual_package, provider))
+                if virtual_package.is_empty() == false && provider.is_empty() == false =>
+            {
+                provider_preferences.insert(String::from(virtual_package), String::from(provider));
+            }
+            _ => {
+                print_message(
+                    "error",
+                    &format!(
+                        "invalid provider preference: \"{preference}\", expected: \"virtual:provider\""
+                    ),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            }
+        };
+    }
+
+    let provider_preferences: HashMap<String, String> = provider_preferences;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{}\"",
+            space_and_truncate_string("no. of provider preferences:", 47),
+            &provider_preferences.len()
+        ),
+        &message_config,
+    );
+
+    let mut preferences: Vec<Preference> = Vec::new();
+
+    if chosen_preferences_location.is_empty() == false {
+        match Path::new(&chosen_preferences_location).canonicalize() {
+            Ok(result) => {
+                chosen_preferences_location = String::from(result.to_string_lossy());
+            }
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("invalid preferences location: \"{chosen_preferences_location}\""),
+                    &message_config,
+                );
+                return ExitCode::from(1);
+            }
+        };
+
+        match parse_preferences_file(&chosen_preferences_location, &message_config) {
+            Ok(result) => preferences = result,
+            Err(..) => return ExitCode::from(1),
+        };
+    } else if install_preferences == true {
+        print_message(
+            "error",
+            "no preferences file was provided to install.",
+            &message_config,
+        );
+        return ExitCode::from(1);
+    };
+
+    let preferences_location: String = chosen_preferences_location;
+    let preferences: Vec<Preference> = preferences;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{}\"",
+            space_and_truncate_string("no. of preferences:", 47),
+            &preferences.len()
+        ),
+        &message_config,
+    );
+
+    // like apt inside a chroot, phased updates are included unless a machine id is provided to
+    // draw with.
+    let phased_updates: String = match &chosen_phased_updates as &str {
+        "" => String::from("always"),
+        _ => chosen_phased_updates,
+    };
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{phased_updates}\"",
+            space_and_truncate_string("phased updates:", 47)
+        ),
+        &message_config,
+    );
+
     //////////////////////////////////////////////
 
     print_message(
@@ -1597,11 +2540,261 @@ See debstrap(8) for more information."
         )
         .unwrap_or(());
 
-        return ExitCode::from(1);
-    };
+        return ExitCode::from(1);
+    };
+
+    if discard_output_on_exit == true {
+        if create_directory(&target_output_directory, &message_config).is_err() == true {
+            clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
+    println!("Fetching indices:");
+
+    let indices_directory: String = format!("{workspace_directory}/indices");
+
+    if create_directory(&indices_directory, &message_config).is_err() == true {
+        clean_up_on_exit(
+            &workspace_directory,
+            None,
+            &target_actions_to_skip,
+            &message_config,
+        )
+        .unwrap_or(());
+
+        return ExitCode::from(1);
+    };
+
+    // the Contents indices are large, so they are only downloaded when files are looked up.
+    if download_indices(
+        &sources_list,
+        use_udebs,
+        files_to_find_then_exit.len() != 0,
+        &indices_directory,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        clean_up_on_exit(
+            &workspace_directory,
+            None,
+            &target_actions_to_skip,
+            &message_config,
+        )
+        .unwrap_or(());
+
+        return ExitCode::from(1);
+    };
+
+    //////////////////////////////////////////////
+
+    println!("Creating package database ...");
+
+    let mut packages_in_source_order: Vec<Package> = Vec::new();
+    let mut string_interner: StringInterner = StringInterner::default();
+
+    for entry in &sources_list {
+        for (scheme, path) in &entry.uris {
+            for suite in &entry.suites {
+                let release_file_name: String =
+                    format!("{path}_dists_{suite}_Release").replace("/", "_");
+
+                let release: Release;
+
+                match parse_release_file(
+                    &format!("{indices_directory}/{release_file_name}"),
+                    &message_config,
+                ) {
+                    Ok(result) => release = result,
+                    Err(..) => {
+                        clean_up_on_exit(
+                            &workspace_directory,
+                            None,
+                            &target_actions_to_skip,
+                            &message_config,
+                        )
+                        .unwrap_or(());
+
+                        return ExitCode::from(1);
+                    }
+                };
+
+                for component in &entry.components {
+                    for architecture in &entry.architectures {
+                        let package_list_path: String =
+                            get_package_list_path(&component, &architecture, use_udebs);
+
+                        let package_list_file_name: String =
+                            format!("{path}/dists/{suite}/{package_list_path}").replace("/", "_");
+
+                        // the cache is keyed by the checksum of the package list in the Release
+                        // file, so it is only used as long as the package list is unchanged.
+                        let mut cache_file: String = String::new();
+                        let mut cached_packages: Option<Vec<Package>> = None;
+
+                        if cache_directory.is_empty() == false {
+                            match release.sha256_hashes.get(&package_list_path) {
+                                Some((hash, _)) => {
+                                    cache_file = format!("{cache_directory}/{hash}");
+                                }
+                                None => {}
+                            };
+                        };
+
+                        if cache_file.is_empty() == false && Path::new(&cache_file).exists() == true
+                        {
+                            match read_index_cache(
+                                &cache_file,
+                                &suite,
+                                &component,
+                                &architecture,
+                                &scheme,
+                                &path,
+                                &mut string_interner,
+                                &message_config,
+                            ) {
+                                Ok(result) => {
+                                    print_message(
+                                        "debug",
+                                        &format!("using index cache: \"{cache_file}\""),
+                                        &message_config,
+                                    );
+
+                                    cached_packages = Some(result);
+                                }
+                                Err(..) => {}
+                            };
+                        };
+
+                        let packages: Vec<Package>;
+
+                        match cached_packages {
+                            Some(result) => packages = result,
+                            None => match std::fs::read_to_string(format!(
+                                "{indices_directory}/{package_list_file_name}"
+                            )) {
+                                Ok(result) => {
+                                    // a malformed entry of a package list is skipped instead of
+                                    // failing the whole run.
+                                    let (paragraphs, mut errors) = parse_deb822_paragraphs(&result);
+
+                                    let mut parsed_packages: Vec<Package> = Vec::new();
+
+                                    for paragraph in &paragraphs {
+                                        match Package::new(
+                                            paragraph,
+                                            &suite,
+                                            &component,
+                                            &architecture,
+                                            &scheme,
+                                            &path,
+                                            &mut string_interner,
+                                        ) {
+                                            Ok(package) => parsed_packages.push(package),
+                                            Err(error) => errors.push(error),
+                                        };
+                                    }
+
+                                    errors.sort_by_key(|error| error.line_number);
+
+                                    for error in &errors {
+                                        print_message(
+                                            "warning",
+                                            &format!(
+                                                "ignoring invalid package: {}",
+                                                format_deb822_error(&package_list_file_name, error)
+                                            ),
+                                            &message_config,
+                                        );
+                                    }
+
+                                    packages = parsed_packages;
+
+                                    // a package list with invalid entries isn't cached, so the
+                                    // warnings are repeated on every run.
+                                    if cache_file.is_empty() == false && errors.len() == 0 {
+                                        print_message(
+                                            "debug",
+                                            &format!("creating index cache: \"{cache_file}\""),
+                                            &message_config,
+                                        );
+
+                                        write_index_cache(&cache_file, &packages, &message_config)
+                                            .unwrap_or(());
+                                    };
+                                }
+                                Err(..) => {
+                                    print_message(
+                                        "error",
+                                        &format!(
+                                        "failed to read package list: \"{package_list_file_name}\""
+                                    ),
+                                        &message_config,
+                                    );
+
+                                    clean_up_on_exit(
+                                        &workspace_directory,
+                                        None,
+                                        &target_actions_to_skip,
+                                        &message_config,
+                                    )
+                                    .unwrap_or(());
+
+                                    return ExitCode::from(1);
+                                }
+                            },
+                        };
+
+                        for mut package in packages {
+                            package.pin_priority =
+                                get_pin_priority(&package, &release, &preferences);
+
+                            if is_phased_update_excluded(&package, &phased_updates) == true {
+                                package.pin_priority = std::cmp::min(package.pin_priority, 1);
+                            };
+
+                            packages_in_source_order.push(package);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let package_database: PackageDatabase = PackageDatabase::new(packages_in_source_order);
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{}\"",
+            space_and_truncate_string("no. of packages in database:", 47),
+            &package_database.len()
+        ),
+        &message_config,
+    );
+
+    let mut source_package_database: HashMap<String, Vec<SourcePackage>> = HashMap::new();
 
-    if discard_output_on_exit == true {
-        if create_directory(&target_output_directory, &message_config).is_err() == true {
+    // the Sources indices are needed to download source packages and to look up the build
+    // dependencies of a source package that isn't provided as a local '.dsc' file.
+    if download_sources == true
+        || (target_build_depends.is_empty() == false
+            && target_build_depends_file.is_empty() == true)
+    {
+        if download_source_indices(&sources_list, &indices_directory, &message_config).is_err()
+            == true
+        {
             clean_up_on_exit(
                 &workspace_directory,
                 None,
@@ -1612,82 +2805,59 @@ See debstrap(8) for more information."
 
             return ExitCode::from(1);
         };
-    };
-
-    //////////////////////////////////////////////
-
-    println!("Fetching indices:");
-
-    let indices_directory: String = format!("{workspace_directory}/indices");
-
-    if create_directory(&indices_directory, &message_config).is_err() == true {
-        clean_up_on_exit(
-            &workspace_directory,
-            None,
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
-
-        return ExitCode::from(1);
-    };
 
-    if download_indices(&sources_list, &indices_directory, &message_config).is_err() == true {
-        clean_up_on_exit(
-            &workspace_directory,
-            None,
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
-
-        return ExitCode::from(1);
-    };
-
-    //////////////////////////////////////////////
-
-    println!("Creating package database ...");
-
-    let mut package_database: HashMap<String, Vec<Package>> = HashMap::new();
-
-    for entry in &sources_list {
-        for (scheme, path) in &entry.uris {
-            for suite in &entry.suites {
-                for component in &entry.components {
-                    for architecture in &entry.architectures {
-                        let package_list_file_name: String = format!(
-                            "{path}/dists/{suite}/{component}/binary-{architecture}_Packages"
-                        )
-                        .replace("/", "_");
+        for entry in &sources_list {
+            for (scheme, path) in &entry.uris {
+                for suite in &entry.suites {
+                    for component in &entry.components {
+                        let source_list_file_name: String =
+                            format!("{path}/dists/{suite}/{component}/source_Sources")
+                                .replace("/", "_");
 
                         match std::fs::read_to_string(format!(
-                            "{indices_directory}/{package_list_file_name}"
+                            "{indices_directory}/{source_list_file_name}"
                         )) {
                             Ok(result) => {
-                                for entry in result
-                                    .trim()
-                                    .split("\n\n")
-                                    .map(|element| String::from(element))
-                                    .collect::<Vec<String>>()
-                                {
-                                    let package: Package = Package::new(
-                                        &entry,
-                                        &suite,
-                                        &component,
-                                        &architecture,
-                                        &scheme,
-                                        &path,
+                                // like a package list, a malformed entry of a source list is
+                                // skipped instead of failing the whole run.
+                                let (paragraphs, mut errors) = parse_deb822_paragraphs(&result);
+
+                                let mut source_packages: Vec<SourcePackage> = Vec::new();
+
+                                for paragraph in &paragraphs {
+                                    match SourcePackage::new(
+                                        paragraph, &suite, &component, &scheme, &path,
+                                    ) {
+                                        Ok(source_package) => source_packages.push(source_package),
+                                        Err(error) => errors.push(error),
+                                    };
+                                }
+
+                                errors.sort_by_key(|error| error.line_number);
+
+                                for error in &errors {
+                                    print_message(
+                                        "warning",
+                                        &format!(
+                                            "ignoring invalid source package: {}",
+                                            format_deb822_error(&source_list_file_name, error)
+                                        ),
+                                        &message_config,
                                     );
+                                }
 
-                                    let package_name: String = package.name.clone();
+                                for source_package in source_packages {
+                                    let source_package_name: String = source_package.name.clone();
 
-                                    match package_database.get_mut(&package_name) {
+                                    match source_package_database.get_mut(&source_package_name) {
                                         Some(result) => {
-                                            result.push(package);
+                                            result.push(source_package);
                                         }
                                         None => {
-                                            package_database
-                                                .insert(package_name, Vec::from([package]));
+                                            source_package_database.insert(
+                                                source_package_name,
+                                                Vec::from([source_package]),
+                                            );
                                         }
                                     };
                                 }
@@ -1696,7 +2866,7 @@ See debstrap(8) for more information."
                                 print_message(
                                     "error",
                                     &format!(
-                                        "failed to read package list: \"{package_list_file_name}\""
+                                        "failed to read source list: \"{source_list_file_name}\""
                                     ),
                                     &message_config,
                                 );
@@ -1716,9 +2886,38 @@ See debstrap(8) for more information."
                 }
             }
         }
-    }
+    };
 
-    let package_database: HashMap<String, Vec<Package>> = package_database;
+    let source_package_database: HashMap<String, Vec<SourcePackage>> = source_package_database;
+
+    //////////////////////////////////////////////
+
+    for (virtual_package, provider) in &provider_preferences {
+        let does_provider_exist: bool = package_database
+            .get_providers(virtual_package)
+            .iter()
+            .any(|package| package.name == *provider);
+
+        if does_provider_exist == false {
+            print_message(
+                "error",
+                &format!(
+                    "preferred provider: \"{provider}\" doesn't provide: \"{virtual_package}\""
+                ),
+                &message_config,
+            );
+
+            clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    }
 
     //////////////////////////////////////////////
 
@@ -1729,20 +2928,40 @@ See debstrap(8) for more information."
         println!();
 
         for package in packages_to_print_then_exit {
-            match package_database.get(&package) {
-                Some(result) => {
+            let mut found_packages: Result<Vec<&Package>, ()> = Err(());
+
+            // a plain name finds the package in every architecture.
+            if package.contains([':', '=', '/']) == true {
+                found_packages = find_packages_by_specification(
+                    &package_database,
+                    &package,
+                    &architecture_config,
+                    &message_config,
+                );
+            } else {
+                let result: Vec<&Package> =
+                    find_packages_by_name(&package_database, &package, &architecture_config);
+
+                match result.len() {
+                    0 => {
+                        print_message(
+                            "error",
+                            &format!("failed to find package: \"{package}\""),
+                            &message_config,
+                        );
+                    }
+                    _ => found_packages = Ok(result),
+                };
+            };
+
+            match found_packages {
+                Ok(result) => {
                     for entry in result {
                         pretty_print_package(&entry, &message_config);
                         println!();
                     }
                 }
-                None => {
-                    print_message(
-                        "error",
-                        &format!("failed to find package: \"{package}\""),
-                        &message_config,
-                    );
-
+                Err(..) => {
                     clean_up_on_exit(
                         &workspace_directory,
                         None,
@@ -1756,6 +2975,176 @@ See debstrap(8) for more information."
             };
         }
 
+        // the files are looked up next if both are asked for.
+        if files_to_find_then_exit.len() == 0 {
+            if clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                return ExitCode::from(1);
+            };
+
+            return ExitCode::from(0);
+        };
+    };
+
+    //////////////////////////////////////////////
+
+    if files_to_find_then_exit.len() != 0 {
+        files_to_find_then_exit.sort_unstable();
+        files_to_find_then_exit.dedup();
+
+        // the paths in the Contents indices have no leading '/'.
+        let patterns: Vec<String> = files_to_find_then_exit
+            .iter()
+            .map(|file| String::from(file.trim_start_matches('/')))
+            .collect::<Vec<String>>();
+
+        // every Contents index is only searched once for all the files, and the packages that ship
+        // a file are looked up in the suite (and component) of the index.
+        let mut searched_contents_indices: Vec<String> = Vec::new();
+        let mut found_files: Vec<(Package, String)> = Vec::new();
+
+        for entry in &sources_list {
+            for (_, path) in &entry.uris {
+                for suite in &entry.suites {
+                    for component in &entry.components {
+                        for architecture in &entry.architectures {
+                            for (index, contents_index_path) in
+                                get_contents_index_paths(&component, &architecture, use_udebs)
+                                    .iter()
+                                    .enumerate()
+                            {
+                                let contents_index_file_name: String =
+                                    format!("{path}/dists/{suite}/{contents_index_path}")
+                                        .replace("/", "_");
+
+                                let contents_index_file: String =
+                                    format!("{indices_directory}/{contents_index_file_name}");
+
+                                if Path::new(&contents_index_file).exists() == false {
+                                    continue;
+                                };
+
+                                if searched_contents_indices.contains(&contents_index_file) == true
+                                {
+                                    break;
+                                };
+
+                                searched_contents_indices.push(contents_index_file.clone());
+
+                                print_message(
+                                    "debug",
+                                    &format!("searching Contents index: \"{contents_index_file}\""),
+                                    &message_config,
+                                );
+
+                                let matches: Vec<(String, String)>;
+
+                                match search_contents_index(
+                                    &contents_index_file,
+                                    &patterns,
+                                    &message_config,
+                                ) {
+                                    Ok(result) => matches = result,
+                                    Err(..) => {
+                                        clean_up_on_exit(
+                                            &workspace_directory,
+                                            None,
+                                            &target_actions_to_skip,
+                                            &message_config,
+                                        )
+                                        .unwrap_or(());
+
+                                        return ExitCode::from(1);
+                                    }
+                                };
+
+                                for (file_path, package_name) in matches {
+                                    for package in package_database
+                                        .get(&package_name, &architecture)
+                                        .iter()
+                                        .chain(package_database.get(&package_name, "all").iter())
+                                    {
+                                        // the first path is the index of the component, the
+                                        // second one of the whole suite.
+                                        if package.origin_uri_path == *path
+                                            && package.origin_suite == *suite
+                                            && (index != 0
+                                                || package.origin_component == *component)
+                                        {
+                                            found_files.push((package.clone(), file_path.clone()));
+                                        };
+                                    }
+                                }
+
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        println!();
+
+        for (file, pattern) in files_to_find_then_exit.iter().zip(patterns.iter()) {
+            let mut found_packages: Vec<(&Package, Vec<String>)> = Vec::new();
+
+            for (package, file_path) in &found_files {
+                if does_contents_path_match(file_path, pattern) == false {
+                    continue;
+                };
+
+                match found_packages.iter_mut().find(|(found_package, _)| {
+                    return found_package.name == package.name
+                        && found_package.version == package.version
+                        && found_package.architecture == package.architecture
+                        && found_package.origin_suite == package.origin_suite
+                        && found_package.origin_component == package.origin_component
+                        && found_package.origin_uri_path == package.origin_uri_path;
+                }) {
+                    Some((_, file_paths)) => {
+                        if file_paths.contains(file_path) == false {
+                            file_paths.push(file_path.clone());
+                        };
+                    }
+                    None => found_packages.push((package, Vec::from([file_path.clone()]))),
+                };
+            }
+
+            if found_packages.len() == 0 {
+                print_message(
+                    "error",
+                    &format!("failed to find file: \"{file}\""),
+                    &message_config,
+                );
+
+                clean_up_on_exit(
+                    &workspace_directory,
+                    None,
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+
+            for (package, file_paths) in &mut found_packages {
+                file_paths.sort_unstable();
+
+                pretty_print_package(&package, &message_config);
+                pretty_print_files(&file_paths, &message_config);
+                println!();
+            }
+        }
+
         if clean_up_on_exit(
             &workspace_directory,
             None,
@@ -1773,89 +3162,92 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
+    // the variants only consist of native packages, foreign ones have to be included explicitly.
+    let native_packages: Vec<&Package> = package_database
+        .get_candidates()
+        .into_iter()
+        .filter(|package| {
+            package.architecture == primary_architecture || package.architecture == "all"
+        })
+        .collect::<Vec<&Package>>();
+
     let mut initial_package_set: Vec<Package> = Vec::new();
 
     match &target_variant as &str {
         "essential" => {
-            for package in package_database.values() {
-                if package[0].is_essential == true || package[0].name == "mawk" {
-                    initial_package_set.push(package[0].clone());
+            for package in &native_packages {
+                if package.is_essential == true || package.name == "mawk" {
+                    initial_package_set.push((*package).clone());
                 };
             }
         }
         "required" => {
-            for package in package_database.values() {
-                if package[0].is_essential == true
-                    || package[0].priority == "required"
-                    || package[0].name == "apt"
+            for package in &native_packages {
+                if package.is_essential == true
+                    || package.priority == "required"
+                    || package.name == "apt"
                 {
-                    initial_package_set.push(package[0].clone());
+                    initial_package_set.push((*package).clone());
                 };
             }
         }
         "buildd" => {
-            for package in package_database.values() {
-                if package[0].is_essential == true
-                    || package[0].priority == "required"
-                    || package[0].is_build_essential == true
-                    || package[0].name == "apt"
-                    || package[0].name == "build-essential"
+            for package in &native_packages {
+                if package.is_essential == true
+                    || package.priority == "required"
+                    || package.is_build_essential == true
+                    || package.name == "apt"
+                    || package.name == "build-essential"
                 {
-                    initial_package_set.push(package[0].clone());
+                    initial_package_set.push((*package).clone());
                 };
             }
         }
         "important" => {
-            for package in package_database.values() {
-                if package[0].is_essential == true
-                    || package[0].priority == "required"
-                    || package[0].priority == "important"
+            for package in &native_packages {
+                if package.is_essential == true
+                    || package.priority == "required"
+                    || package.priority == "important"
                 {
-                    initial_package_set.push(package[0].clone());
+                    initial_package_set.push((*package).clone());
                 };
             }
         }
         "standard" => {
-            for package in package_database.values() {
-                if package[0].is_essential == true
-                    || package[0].priority == "required"
-                    || package[0].priority == "important"
-                    || package[0].priority == "standard"
+            for package in &native_packages {
+                if package.is_essential == true
+                    || package.priority == "required"
+                    || package.priority == "important"
+                    || package.priority == "standard"
                 {
-                    initial_package_set.push(package[0].clone());
+                    initial_package_set.push((*package).clone());
                 };
             }
         }
         "custom" => {
-            for custom in &custom_package_set {
-                let mut is_package_present: bool = false;
-
-                for package in package_database.values() {
-                    if package[0].name == *custom {
-                        initial_package_set.push(package[0].clone());
-
-                        is_package_present = true;
-
-                        break;
-                    };
-                }
-
-                if is_package_present == false {
-                    print_message(
-                        "error",
-                        &format!("failed to find package: \"{custom}\""),
-                        &message_config,
-                    );
-
-                    clean_up_on_exit(
-                        &workspace_directory,
-                        None,
-                        &target_actions_to_skip,
-                        &message_config,
-                    )
-                    .unwrap_or(());
+            for custom in &apply_package_list_restrictions(
+                &custom_package_set,
+                &primary_architecture,
+                &build_profiles,
+            ) {
+                match find_packages_by_specification(
+                    &package_database,
+                    &custom,
+                    &architecture_config,
+                    &message_config,
+                ) {
+                    Ok(result) => initial_package_set.push(result[0].clone()),
+                    Err(..) => {
+                        clean_up_on_exit(
+                            &workspace_directory,
+                            None,
+                            &target_actions_to_skip,
+                            &message_config,
+                        )
+                        .unwrap_or(());
 
-                    return ExitCode::from(1);
+                        return ExitCode::from(1);
+                    }
                 };
             }
         }
@@ -1867,44 +3259,93 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
-    if packages_to_include.len() != 0 {
-        for included in &packages_to_include {
-            if initial_package_set
-                .iter()
-                .any(|initial| &initial.name == included)
-                == true
-            {
-                print_message(
-                    "debug",
-                    &format!("package present: \"{included}\""),
+    if target_build_depends.is_empty() == false {
+        let build_depends_source_package: Result<SourcePackage, ()>;
+
+        if target_build_depends_file.is_empty() == false {
+            build_depends_source_package =
+                read_source_package_file(&target_build_depends_file, &message_config);
+        } else {
+            match source_package_database.get(&target_build_depends) {
+                Some(result) => build_depends_source_package = Ok(result[0].clone()),
+                None => {
+                    print_message(
+                        "error",
+                        &format!("failed to find source package: \"{target_build_depends}\""),
+                        &message_config,
+                    );
+
+                    build_depends_source_package = Err(());
+                }
+            };
+        };
+
+        match build_depends_source_package {
+            Ok(source_package) => {
+                match find_build_dependencies(
+                    &package_database,
+                    &source_package,
+                    &primary_architecture,
+                    &build_profiles,
+                    &provider_preferences,
+                    &architecture_config,
                     &message_config,
-                );
-            } else {
-                let mut is_package_present: bool = false;
+                ) {
+                    Ok(result) => {
+                        for package in result {
+                            print_message(
+                                "debug",
+                                &format!("build dependency added: \"{}\"", package.name),
+                                &message_config,
+                            );
+
+                            initial_package_set.push(package);
+                        }
+                    }
+                    Err(..) => {
+                        clean_up_on_exit(
+                            &workspace_directory,
+                            None,
+                            &target_actions_to_skip,
+                            &message_config,
+                        )
+                        .unwrap_or(());
 
-                for package in package_database.values() {
-                    if package[0].name == *included {
-                        initial_package_set.push(package[0].clone());
+                        return ExitCode::from(1);
+                    }
+                };
+            }
+            Err(..) => {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    None,
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
 
-                        print_message(
-                            "debug",
-                            &format!("package added:   \"{included}\""),
-                            &message_config,
-                        );
+                return ExitCode::from(1);
+            }
+        };
 
-                        is_package_present = true;
+        initial_package_set.sort_unstable();
+        initial_package_set.dedup();
+    };
 
-                        break;
-                    };
-                }
+    //////////////////////////////////////////////
 
-                if is_package_present == false {
-                    print_message(
-                        "error",
-                        &format!("failed to find package: \"{included}\""),
-                        &message_config,
-                    );
+    if packages_to_include.len() != 0 {
+        for included in &packages_to_include {
+            let selected: Package;
 
+            match find_packages_by_specification(
+                &package_database,
+                &included,
+                &architecture_config,
+                &message_config,
+            ) {
+                Ok(result) => selected = result[0].clone(),
+                Err(..) => {
                     clean_up_on_exit(
                         &workspace_directory,
                         None,
@@ -1914,7 +3355,29 @@ See debstrap(8) for more information."
                     .unwrap_or(());
 
                     return ExitCode::from(1);
-                };
+                }
+            };
+
+            if initial_package_set.contains(&selected) == true {
+                print_message(
+                    "debug",
+                    &format!("package present: \"{included}\""),
+                    &message_config,
+                );
+            } else {
+                // a version or suite that was selected explicitly replaces the one of the variant.
+                initial_package_set.retain(|initial| {
+                    format_package_name(initial, &architecture_config)
+                        != format_package_name(&selected, &architecture_config)
+                });
+
+                initial_package_set.push(selected);
+
+                print_message(
+                    "debug",
+                    &format!("package added:   \"{included}\""),
+                    &message_config,
+                );
             };
         }
 
@@ -1925,11 +3388,11 @@ See debstrap(8) for more information."
         for excluded in &packages_to_exclude {
             if initial_package_set
                 .iter()
-                .any(|initial| &initial.name == excluded)
+                .any(|initial| format_package_name(initial, &architecture_config) == *excluded)
                 == true
             {
                 for (index, initial) in initial_package_set.iter_mut().enumerate() {
-                    if initial.name == *excluded {
+                    if format_package_name(initial, &architecture_config) == *excluded {
                         initial_package_set.remove(index);
 
                         print_message(
@@ -1999,6 +3462,64 @@ See debstrap(8) for more information."
                 &initial_package_set,
                 &consider_recommends,
                 &packages_to_prohibit,
+                &provider_preferences,
+                &architecture_config,
+                &message_config,
+            ) {
+                Ok(result) => {
+                    target_package_set = result;
+                }
+                Err(..) => {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        None,
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+                    return ExitCode::from(1);
+                }
+            };
+        }
+        "sat" => {
+            println!("Calculating dependencies ...");
+
+            match solve_dependencies(
+                &package_database,
+                &initial_package_set,
+                &consider_recommends,
+                &packages_to_prohibit,
+                &provider_preferences,
+                &architecture_config,
+                &message_config,
+            ) {
+                Ok(result) => {
+                    target_package_set = result;
+                }
+                Err(..) => {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        None,
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+                    return ExitCode::from(1);
+                }
+            };
+        }
+        _ if target_resolver.starts_with("edsp:") == true => {
+            println!("Calculating dependencies ...");
+
+            match solve_dependencies_externally(
+                &target_resolver.replacen("edsp:", "", 1),
+                &workspace_directory,
+                &package_database,
+                &initial_package_set,
+                &consider_recommends,
+                &packages_to_prohibit,
+                &primary_architecture,
+                &list_of_target_architectures,
                 &message_config,
             ) {
                 Ok(result) => {
@@ -2024,6 +3545,126 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
+    let mut target_filesystem_uuid: String = String::new();
+
+    match &target_output_format as &str {
+        "ext4" | "img" => match source_date_epoch {
+            // the UUID is derived from everything that makes up the image, so the same bootstrap
+            // produces the same image but different images don't share a UUID.
+            Some(epoch) => {
+                let mut seed: String = format!(
+                    "filesystem {epoch} {target_output_format} {} {} {target_variant}\n",
+                    sources_list
+                        .iter()
+                        .flat_map(|entry| entry.suites.iter().cloned())
+                        .collect::<Vec<String>>()
+                        .join(","),
+                    list_of_target_architectures.join(","),
+                );
+
+                for package in &target_package_set {
+                    seed.push_str(&format!(
+                        "{} {} {}\n",
+                        package.name, package.architecture, package.version
+                    ));
+                }
+
+                target_filesystem_uuid = derive_uuid(&seed);
+            }
+            None => target_filesystem_uuid = generate_uuid(),
+        },
+        _ => {}
+    };
+
+    let target_filesystem_uuid: String = target_filesystem_uuid;
+
+    print_message(
+        "debug",
+        &format!(
+            "{} \"{target_filesystem_uuid}\"",
+            space_and_truncate_string("target filesystem UUID:", 47)
+        ),
+        &message_config,
+    );
+
+    //////////////////////////////////////////////
+
+    if target_graph_file.is_empty() == false {
+        if create_dependency_graph(
+            &target_package_set,
+            &target_graph_file,
+            &target_graph_format,
+            &architecture_config,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
+    if packages_to_explain.len() != 0 || packages_to_explain_absence_of.len() != 0 {
+        let mut was_explanation_successful: bool = true;
+
+        for package in &packages_to_explain {
+            if explain_why(
+                &package,
+                &initial_package_set,
+                &target_package_set,
+                &packages_to_include,
+                &consider_recommends,
+                &architecture_config,
+            )
+            .is_err()
+                == true
+            {
+                was_explanation_successful = false;
+            };
+        }
+
+        for package in &packages_to_explain_absence_of {
+            if explain_why_not(
+                &package,
+                &package_database,
+                &target_package_set,
+                &packages_to_exclude,
+                &packages_to_prohibit,
+                &consider_recommends,
+                &architecture_config,
+            )
+            .is_err()
+                == true
+            {
+                was_explanation_successful = false;
+            };
+        }
+
+        if was_explanation_successful == false {
+            clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
     if only_action_then_exit == "print_target_set" || only_action_then_exit == "print_both_sets" {
         println!("");
 
@@ -2186,6 +3827,53 @@ See debstrap(8) for more information."
         return ExitCode::from(1);
     };
 
+    let workspace_sources_directory: String = format!("{workspace_directory}/sources");
+
+    if download_sources == true {
+        println!("Downloading source packages ...");
+
+        let source_packages: Vec<SourcePackage>;
+
+        match find_source_packages(
+            &source_package_database,
+            &target_package_set,
+            &message_config,
+        ) {
+            Ok(result) => source_packages = result,
+            Err(..) => {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    None,
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            }
+        };
+
+        if create_directory(&workspace_sources_directory, &message_config).is_err() == true
+            || download_source_packages(
+                &source_packages,
+                &workspace_sources_directory,
+                &message_config,
+            )
+            .is_err()
+                == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                None,
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
     //////////////////////////////////////////////
 
     if download_hooks.len() != 0 {
@@ -2258,38 +3946,95 @@ See debstrap(8) for more information."
             "tarball" => {
                 println!("Packaging (this might take a while) ...");
 
-                print_message(
-                    "debug",
-                    &format!("creating tarball: \"{target_output_directory}/{target_output_file_name}.tar\""),
+                let mut downloaded_package_file_names: Vec<String> =
+                    std::fs::read_dir(&downloaded_packages_directory)
+                        .unwrap()
+                        .map(|element| {
+                            String::from(
+                                element
+                                    .unwrap()
+                                    .path()
+                                    .file_name()
+                                    .unwrap()
+                                    .to_string_lossy(),
+                            )
+                        })
+                        .filter(|file_name| {
+                            file_name.ends_with(".deb") == true
+                                || file_name.ends_with(".udeb") == true
+                        })
+                        .collect::<Vec<String>>();
+
+                downloaded_package_file_names.sort_unstable();
+
+                if create_tarball(
+                    &downloaded_packages_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &Some(downloaded_package_file_names),
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        None,
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                };
+            }
+            _ => {}
+        };
+
+        if download_sources == true {
+            if bundle_source_packages(
+                &workspace_sources_directory,
+                &target_sources_directory,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    None,
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        };
+
+        if target_manifest_file.is_empty() == false {
+            if create_manifest(
+                &target_package_set,
+                &target_manifest_file,
+                &target_manifest_format,
+                DEBSTRAP_VERSION,
+                &source_date_epoch,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    None,
+                    &target_actions_to_skip,
                     &message_config,
-                );
-
-                if run_cmd!(
-                    bash -c "
-                        cd '$downloaded_packages_directory'
-                        tar --create --file='$target_output_directory/$target_output_file_name.tar' *.deb
-                    " 2> /dev/stdout
                 )
-                .is_err()
-                    == true
-                {
-                    print_message(
-                        "error",
-                        &format!("failed to create tarball: \"{target_output_directory}/{target_output_file_name}.tar\""),
-                        &message_config,
-                    );
-
-                    clean_up_on_exit(
-                        &workspace_directory,
-                        None,
-                        &target_actions_to_skip,
-                        &message_config,
-                    ).unwrap_or(());
+                .unwrap_or(());
 
-                    return ExitCode::from(1);
-                };
-            }
-            _ => {}
+                return ExitCode::from(1);
+            };
         };
 
         println!("Package download complete.");
@@ -2353,12 +4098,17 @@ See debstrap(8) for more information."
 
     if extract_only_essentials == true {
         if separate_packages_by_priority(
-            &package_database,
+            &SubsetConfig {
+                package_database: &package_database,
+                target_resolver: &target_resolver,
+                packages_to_prohibit: &packages_to_prohibit,
+                packages_to_consider_essential: &packages_to_consider_essential,
+                packages_to_consider_non_essential: &packages_to_consider_non_essential,
+                provider_preferences: &provider_preferences,
+                architecture_config: &architecture_config,
+            },
             &downloaded_packages_directory,
             &target_package_set,
-            &packages_to_prohibit,
-            &packages_to_consider_essential,
-            &packages_to_consider_non_essential,
             &target_extractor,
             &all_packages_directory,
             &message_config,
@@ -2721,10 +4471,13 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
-    if target_package_set
-        .iter()
-        .any(|package| package.name == "dash")
-        == false
+    // the shell of the debian-installer is provided by busybox, and the links are only needed to
+    // run maintainer scripts.
+    if use_udebs == false
+        && target_package_set
+            .iter()
+            .any(|package| package.name == "dash")
+            == false
     {
         print_message(
             "debug",
@@ -2858,6 +4611,29 @@ See debstrap(8) for more information."
 
     //////////////////////////////////////////////
 
+    // the udebs don't ship the directory of the package database, unlike dpkg.
+    if use_udebs == true {
+        for directory in ["var", "var/lib", "var/lib/dpkg"] {
+            if create_directory(
+                &format!("{target_bootstrap_directory}/{directory}"),
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        }
+    };
+
     if create_file(
         &format!("{target_bootstrap_directory}/var/lib/dpkg/status"),
         "",
@@ -2897,45 +4673,293 @@ See debstrap(8) for more information."
         )
         .unwrap_or(());
 
-        return ExitCode::from(1);
-    };
+        return ExitCode::from(1);
+    };
+
+    if create_file(
+        &format!("{target_bootstrap_directory}/var/lib/dpkg/arch"),
+        "",
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        clean_up_on_exit(
+            &workspace_directory,
+            Some(&target_bootstrap_directory),
+            &target_actions_to_skip,
+            &message_config,
+        )
+        .unwrap_or(());
+
+        return ExitCode::from(1);
+    };
+
+    for architecture in &list_of_target_architectures {
+        print_message(
+            "debug",
+            &format!("adding architecture: \"{architecture}\" to \"{target_bootstrap_directory}/var/lib/dpkg/arch\""),
+            &message_config,
+        );
+
+        if append_file(
+            &format!("{target_bootstrap_directory}/var/lib/dpkg/arch"),
+            &format!("{architecture}\n"),
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    }
+
+    //////////////////////////////////////////////
+
+    // the debian-installer brings its own configuration files, which the defaults of a base
+    // system would overwrite.
+    if use_udebs == false {
+        print_message(
+            "debug",
+            &format!("creating default file: \"{target_bootstrap_directory}/etc/fstab\""),
+            &message_config,
+        );
+
+        let mut fstab_contents: String = String::from(
+            "\
+# UNCONFIGURED FSTAB FOR BASE SYSTEM
+
+# See fstab(5) for more information.
+",
+        );
+
+        if target_filesystem_uuid.is_empty() == false {
+            fstab_contents.push_str(&format!(
+                "\nUUID={target_filesystem_uuid} / ext4 errors=remount-ro 0 1\n"
+            ));
+        };
+
+        if create_file(
+            &format!("{target_bootstrap_directory}/etc/fstab"),
+            &fstab_contents,
+            None,
+            None,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+
+        print_message(
+            "debug",
+            &format!("creating default file: \"{target_bootstrap_directory}/etc/hosts\""),
+            &message_config,
+        );
+
+        if create_file(
+            &format!("{target_bootstrap_directory}/etc/hosts"),
+            "\
+# UNCONFIGURED HOSTS FOR BASE SYSTEM
+
+# Example with hostname 'debian' for IPv4 and IPv6 capable hosts:
+#127.0.0.1  localhost
+#127.0.1.1  debian.localdomain  debian
+#::1        localhost ip6-localhost ip6-loopback
+#ff02::1    ip6-allnodes
+#ff02::2    ip6-allrouters
+
+# See hosts(5) for more information.
+",
+            None,
+            None,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
+    if target_package_set
+        .iter()
+        .any(|package| package.name == "apt")
+        == true
+    {
+        match &sources_list_format as &str {
+            "deb822-style" => {
+                if create_directory(
+                    &format!("{target_bootstrap_directory}/etc/apt/sources.list.d"),
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                };
+
+                if create_sources_list_file(
+                    &sources_list,
+                    &sources_list_format,
+                    &format!("{target_bootstrap_directory}/etc/apt/sources.list.d"),
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                };
+            }
+            "one-line-style" => {
+                if create_sources_list_file(
+                    &sources_list,
+                    &sources_list_format,
+                    &format!("{target_bootstrap_directory}/etc/apt"),
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                };
+            }
+            _ => {}
+        };
+
+        if install_preferences == true {
+            print_message(
+                "debug",
+                &format!("installing preferences file: \"{target_bootstrap_directory}/etc/apt/preferences.d/debstrap.pref\""),
+                &message_config,
+            );
+
+            if create_directory(
+                &format!("{target_bootstrap_directory}/etc/apt/preferences.d"),
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
 
-    if create_file(
-        &format!("{target_bootstrap_directory}/var/lib/dpkg/arch"),
-        "",
-        None,
-        None,
-        &message_config,
-    )
-    .is_err()
-        == true
-    {
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+            // apt ignores files in 'preferences.d' unless they have no or the '.pref' extension.
+            if std::fs::copy(
+                &preferences_location,
+                format!("{target_bootstrap_directory}/etc/apt/preferences.d/debstrap.pref"),
+            )
+            .is_err()
+                == true
+            {
+                print_message(
+                    "error",
+                    &format!("failed to install preferences file: \"{preferences_location}\""),
+                    &message_config,
+                );
 
-        return ExitCode::from(1);
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        };
     };
 
-    for architecture in &list_of_target_architectures {
+    //////////////////////////////////////////////
+
+    if use_udebs == false
+        && merge_usr_directories == false
+        && is_split_usr_supported(&primary_suite) == false
+    {
         print_message(
             "debug",
-            &format!("adding architecture: \"{architecture}\" to \"{target_bootstrap_directory}/var/lib/dpkg/arch\""),
+            &format!("creating warning file: \"{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion\""),
             &message_config,
         );
 
-        if append_file(
-            &format!("{target_bootstrap_directory}/var/lib/dpkg/arch"),
-            &format!("{architecture}\n"),
+        if create_file(
+            &format!("{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion"),
+            "this system will not be supported in the future",
+            None,
+            None,
             &message_config,
         )
         .is_err()
             == true
         {
+            print_message(
+                "error",
+                &format!("failed to create file: \"{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion\""),
+                &message_config,
+            );
+
             clean_up_on_exit(
                 &workspace_directory,
                 Some(&target_bootstrap_directory),
@@ -2946,90 +4970,119 @@ See debstrap(8) for more information."
 
             return ExitCode::from(1);
         };
-    }
+    };
 
     //////////////////////////////////////////////
 
-    print_message(
-        "debug",
-        &format!("creating default file: \"{target_bootstrap_directory}/etc/fstab\""),
-        &message_config,
-    );
-
-    if create_file(
-        &format!("{target_bootstrap_directory}/etc/fstab"),
-        "\
-# UNCONFIGURED FSTAB FOR BASE SYSTEM
-
-# See fstab(5) for more information.
-",
-        None,
-        None,
-        &message_config,
-    )
-    .is_err()
-        == true
-    {
-        clean_up_on_exit(
+    if extract_hooks.len() != 0 {
+        run_hooks(
+            "extract",
+            &extract_hooks,
             &workspace_directory,
+            Some(&all_packages_directory),
             Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
             &message_config,
-        )
-        .unwrap_or(());
-
-        return ExitCode::from(1);
+        );
     };
 
-    print_message(
-        "debug",
-        &format!("creating default file: \"{target_bootstrap_directory}/etc/hosts\""),
-        &message_config,
-    );
+    //////////////////////////////////////////////
 
-    if create_file(
-        &format!("{target_bootstrap_directory}/etc/hosts"),
-        "\
-# UNCONFIGURED HOSTS FOR BASE SYSTEM
+    if only_action_then_exit == "extract_packages" {
+        match &target_output_format as &str {
+            "tarball" => {
+                println!("Packaging (this might take a while) ...");
 
-# Example with hostname 'debian' for IPv4 and IPv6 capable hosts:
-#127.0.0.1  localhost
-#127.0.1.1  debian.localdomain  debian
-#::1        localhost ip6-localhost ip6-loopback
-#ff02::1    ip6-allnodes
-#ff02::2    ip6-allrouters
+                if create_tarball(
+                    &target_bootstrap_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &None,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
 
-# See hosts(5) for more information.
-",
-        None,
-        None,
-        &message_config,
-    )
-    .is_err()
-        == true
-    {
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+                    return ExitCode::from(1);
+                };
+            }
+            "squashfs" => {
+                println!("Packaging (this might take a while) ...");
 
-        return ExitCode::from(1);
-    };
+                if create_squashfs_image(
+                    &target_bootstrap_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &target_compression,
+                    &source_date_epoch,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
 
-    //////////////////////////////////////////////
+                    return ExitCode::from(1);
+                };
+            }
+            "ext4" | "img" => {
+                println!("Packaging (this might take a while) ...");
 
-    if target_package_set
-        .iter()
-        .any(|package| package.name == "apt")
-        == true
-    {
-        match &sources_list_format as &str {
-            "deb822-style" => {
-                if create_directory(
-                    &format!("{target_bootstrap_directory}/etc/apt/sources.list.d"),
+                let partition_type: Option<String>;
+
+                match &target_output_format as &str {
+                    "img" => {
+                        partition_type = Some(default_root_partition_type(&primary_architecture));
+                    }
+                    _ => partition_type = None,
+                };
+
+                let image_size: u64;
+
+                match calculate_image_size(
+                    &target_bootstrap_directory,
+                    &target_image_headroom_percentage,
+                    &target_image_headroom_size,
+                    &message_config,
+                ) {
+                    Ok(result) => image_size = result,
+                    Err(..) => {
+                        clean_up_on_exit(
+                            &workspace_directory,
+                            Some(&target_bootstrap_directory),
+                            &target_actions_to_skip,
+                            &message_config,
+                        )
+                        .unwrap_or(());
+
+                        return ExitCode::from(1);
+                    }
+                };
+
+                if create_ext4_image(
+                    &target_bootstrap_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &image_size,
+                    &target_filesystem_uuid,
+                    &partition_type,
+                    &source_date_epoch,
                     &message_config,
                 )
                 .is_err()
@@ -3045,11 +5098,21 @@ See debstrap(8) for more information."
 
                     return ExitCode::from(1);
                 };
+            }
+            "oci" | "oci-archive" => {
+                println!("Packaging (this might take a while) ...");
 
-                if create_sources_list_file(
-                    &sources_list,
-                    &sources_list_format,
-                    &format!("{target_bootstrap_directory}/etc/apt/sources.list.d"),
+                if create_oci_image(
+                    &target_bootstrap_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &(target_output_format == "oci-archive"),
+                    &Vec::from([(None, Vec::new())]),
+                    &primary_architecture,
+                    &primary_suite,
+                    &workspace_directory,
+                    &source_date_epoch,
                     &message_config,
                 )
                 .is_err()
@@ -3066,11 +5129,17 @@ See debstrap(8) for more information."
                     return ExitCode::from(1);
                 };
             }
-            "one-line-style" => {
-                if create_sources_list_file(
-                    &sources_list,
-                    &sources_list_format,
-                    &format!("{target_bootstrap_directory}/etc/apt"),
+            "cpio" => {
+                println!("Packaging (this might take a while) ...");
+
+                if create_cpio_archive(
+                    &target_bootstrap_directory,
+                    &format!(
+                        "{target_output_directory}/{target_output_file_name}{output_file_suffix}"
+                    ),
+                    &target_compression,
+                    &workspace_directory,
+                    &source_date_epoch,
                     &message_config,
                 )
                 .is_err()
@@ -3084,38 +5153,92 @@ See debstrap(8) for more information."
                     )
                     .unwrap_or(());
 
-                    return ExitCode::from(1);
-                };
-            }
-            _ => {}
+                    return ExitCode::from(1);
+                };
+            }
+            _ => {}
+        };
+
+        if download_sources == true {
+            if bundle_source_packages(
+                &workspace_sources_directory,
+                &target_sources_directory,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        };
+
+        if target_manifest_file.is_empty() == false {
+            if create_manifest(
+                &target_package_set,
+                &target_manifest_file,
+                &target_manifest_format,
+                DEBSTRAP_VERSION,
+                &source_date_epoch,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        };
+
+        println!("Package extraction complete.");
+
+        if clean_up_on_exit(
+            &workspace_directory,
+            Some(&target_bootstrap_directory),
+            &target_actions_to_skip,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            return ExitCode::from(1);
         };
+
+        return ExitCode::from(0);
     };
 
-    //////////////////////////////////////////////
+    ////////////////////////////////////////////////////////////////////////////////////////////////
 
-    if merge_usr_directories == false && is_split_usr_supported(&primary_suite) == false {
-        print_message(
-            "debug",
-            &format!("creating warning file: \"{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion\""),
-            &message_config,
-        );
+    println!("Installing packages:");
 
-        if create_file(
-            &format!("{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion"),
-            "this system will not be supported in the future",
-            None,
-            None,
+    let mut essential_directory_tree: HashMap<String, String> = HashMap::new();
+
+    if use_udebs == true {
+        // like 'dpkg --unpack' in the build of the debian-installer, the maintainer scripts are only
+        // put in place, they are run by the debian-installer when it configures the udebs.
+        let control_directory: String = format!("{workspace_directory}/control");
+
+        if create_directory(
+            &format!("{target_bootstrap_directory}/var/lib/dpkg/info"),
             &message_config,
         )
         .is_err()
             == true
         {
-            print_message(
-                "error",
-                &format!("failed to create file: \"{target_bootstrap_directory}/etc/unsupported-skip-usrmerge-conversion\""),
-                &message_config,
-            );
-
             clean_up_on_exit(
                 &workspace_directory,
                 Some(&target_bootstrap_directory),
@@ -3126,46 +5249,49 @@ See debstrap(8) for more information."
 
             return ExitCode::from(1);
         };
-    };
-
-    //////////////////////////////////////////////
 
-    if extract_hooks.len() != 0 {
-        run_hooks(
-            "extract",
-            &extract_hooks,
-            &workspace_directory,
-            Some(&all_packages_directory),
-            Some(&target_bootstrap_directory),
-            &message_config,
-        );
-    };
+        let mut counter: u16 = 0;
 
-    //////////////////////////////////////////////
+        for package in &packages_to_extract {
+            counter += 1;
 
-    if only_action_then_exit == "extract_packages" {
-        match &target_output_format as &str {
-            "tarball" => {
-                println!("Packaging (this might take a while) ...");
+            println!(
+                "{} {package}",
+                space_and_truncate_string(
+                    &format!("({counter}/{}):", packages_to_extract.len()),
+                    counter_spacing,
+                )
+            );
 
-                print_message(
-                    "debug",
-                    &format!("creating tarball: \"{target_output_directory}/{target_output_file_name}.tar\""),
+            if create_directory(&control_directory, &message_config).is_err() == true
+                || extract_deb_control(
+                    &target_extractor,
+                    &format!("{all_packages_directory}/essential/{package}"),
+                    &control_directory,
                     &message_config,
-                );
-
-                if run_cmd!(
-                    bash -c "
-                        cd '$target_bootstrap_directory'
-                        tar --create --file='$target_output_directory/$target_output_file_name.tar' *
-                    " 2> /dev/stdout
                 )
                 .is_err()
                     == true
-                {
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+
+            let control: String;
+
+            match std::fs::read_to_string(format!("{control_directory}/control")) {
+                Ok(result) => control = result,
+                Err(..) => {
                     print_message(
                         "error",
-                        &format!("failed to create tarball: \"{target_output_directory}/{target_output_file_name}.tar\""),
+                        &format!("failed to read control file of package: \"{package}\""),
                         &message_config,
                     );
 
@@ -3174,181 +5300,216 @@ See debstrap(8) for more information."
                         Some(&target_bootstrap_directory),
                         &target_actions_to_skip,
                         &message_config,
-                    ).unwrap_or(());
+                    )
+                    .unwrap_or(());
 
                     return ExitCode::from(1);
-                };
-            }
-            _ => {}
-        };
-
-        println!("Package extraction complete.");
+                }
+            };
 
-        if clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .is_err()
-            == true
-        {
-            return ExitCode::from(1);
-        };
+            let (paragraphs, _) = parse_deb822_paragraphs(&control);
 
-        return ExitCode::from(0);
-    };
+            let package_name: String;
 
-    ////////////////////////////////////////////////////////////////////////////////////////////////
+            match paragraphs
+                .first()
+                .and_then(|paragraph| paragraph.get("Package"))
+            {
+                Some(result) => package_name = String::from(result),
+                None => {
+                    print_message(
+                        "error",
+                        &format!("invalid control file of package: \"{package}\""),
+                        &message_config,
+                    );
 
-    println!("Installing packages:");
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
 
-    print_message(
-        "debug",
-        &format!("creating temporary file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""),
-        &message_config,
-    );
+                    return ExitCode::from(1);
+                }
+            };
 
-    if create_file(
-        &format!("{target_bootstrap_directory}/usr/sbin/policy-rc.d"),
-        "#! /bin/sh\n\nexit 101\n",
-        Some(0o755),
-        None,
-        &message_config,
-    )
-    .is_err()
-        == true
-    {
-        print_message(
-            "error",
-            &format!(
-                "failed to create file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
-            ),
-            &message_config,
-        );
+            //////////////////////////////////
 
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+            let mut control_file_names: Vec<String> = std::fs::read_dir(&control_directory)
+                .unwrap()
+                .map(|element| {
+                    String::from(
+                        element
+                            .unwrap()
+                            .path()
+                            .file_name()
+                            .unwrap()
+                            .to_string_lossy(),
+                    )
+                })
+                .filter(|file_name| file_name != "control")
+                .collect::<Vec<String>>();
 
-        return ExitCode::from(1);
-    };
+            control_file_names.sort_unstable();
 
-    let mut start_stop_daemon_location: String = String::new();
+            for file_name in &control_file_names {
+                print_message(
+                    "debug",
+                    &format!("moving file: \"{control_directory}/{file_name}\" to \"{target_bootstrap_directory}/var/lib/dpkg/info/{package_name}.{file_name}\""),
+                    &message_config,
+                );
 
-    if Path::new(&format!(
-        "{target_bootstrap_directory}/sbin/start-stop-daemon"
-    ))
-    .is_file()
-        == true
-    {
-        start_stop_daemon_location = format!("{target_bootstrap_directory}/sbin/start-stop-daemon");
-    } else if Path::new(&format!(
-        "{target_bootstrap_directory}/usr/sbin/start-stop-daemon"
-    ))
-    .is_file()
-        == true
-    {
-        start_stop_daemon_location =
-            format!("{target_bootstrap_directory}/usr/sbin/start-stop-daemon");
-    };
+                if move_file(
+                    format!("{control_directory}/{file_name}"),
+                    format!(
+                        "{target_bootstrap_directory}/var/lib/dpkg/info/{package_name}.{file_name}"
+                    ),
+                )
+                .is_err()
+                    == true
+                {
+                    print_message(
+                        "error",
+                        &format!("failed to move file: \"{control_directory}/{file_name}\""),
+                        &message_config,
+                    );
 
-    let start_stop_daemon_location: String = start_stop_daemon_location;
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
 
-    print_message(
-        "debug",
-        &format!("renaming file: \"{start_stop_daemon_location}\" to \"{start_stop_daemon_location}.ORIGINAL\""),
-        &message_config,
-    );
-    if std::fs::rename(
-        format!("{start_stop_daemon_location}"),
-        format!("{start_stop_daemon_location}.ORIGINAL"),
-    )
-    .is_err()
-        == true
-    {
-        print_message(
-            "error",
-            &format!("failed to rename file: \"{start_stop_daemon_location}\""),
-            &message_config,
-        );
+                    return ExitCode::from(1);
+                };
+            }
 
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+            // the status of the udeb is added after its name, like dpkg does.
+            let mut status_entry: String =
+                format!("Package: {package_name}\nStatus: install ok unpacked\n");
 
-        return ExitCode::from(1);
-    };
+            for line in control.lines() {
+                if line.starts_with("Package:") == false && line.trim().is_empty() == false {
+                    status_entry.push_str(&format!("{line}\n"));
+                };
+            }
 
-    print_message(
-        "debug",
-        &format!("creating temporary file: \"{start_stop_daemon_location}\""),
-        &message_config,
-    );
+            if counter > 1 {
+                status_entry = format!("\n{status_entry}");
+            };
 
-    if create_file(
-        &format!("{start_stop_daemon_location}"),
-        "#! /bin/sh\n\necho -e '\\nWarning: Fake start-stop-daemon called, doing nothing'\n\nexit 0\n",
-        Some(0o755),
-        None,
-        &message_config,
-    ).is_err() == true {
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        ).unwrap_or(());
+            if append_file(
+                &format!("{target_bootstrap_directory}/var/lib/dpkg/status"),
+                &status_entry,
+                &message_config,
+            )
+            .is_err()
+                == true
+                || remove_directory(&control_directory, &message_config).is_err() == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
 
-        return ExitCode::from(1);
-    };
+                return ExitCode::from(1);
+            };
+        }
 
-    //////////////////////////////////////////////
+        //////////////////////////////////////
 
-    if mount_virtual_kernel_file_systems(&target_bootstrap_directory, &message_config).is_err()
-        == true
-    {
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
+        if target_hooks.len() != 0 {
+            run_hooks(
+                "target",
+                &target_hooks,
+                &workspace_directory,
+                Some(&all_packages_directory),
+                Some(&target_bootstrap_directory),
+                &message_config,
+            );
+        };
+    } else {
+        print_message(
+            "debug",
+            &format!(
+                "creating temporary file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
+            ),
+            &message_config,
+        );
+
+        if create_file(
+            &format!("{target_bootstrap_directory}/usr/sbin/policy-rc.d"),
+            "#! /bin/sh\n\nexit 101\n",
+            Some(0o755),
+            None,
             &message_config,
         )
-        .unwrap_or(());
+        .is_err()
+            == true
+        {
+            print_message(
+                "error",
+                &format!(
+                    "failed to create file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
+                ),
+                &message_config,
+            );
 
-        return ExitCode::from(1);
-    };
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
 
-    if target_package_set
-        .iter()
-        .any(|package| package.name == "dash")
-        == false
-    {
-        if run_cmd!(
-            chroot "$target_bootstrap_directory" /usr/bin/env --ignore-environment bash -c "
-export HOME='/root'
-export TERM='$term_environment_variable'
-export PATH
+            return ExitCode::from(1);
+        };
 
-update-alternatives --force --install /bin/sh sh /bin/bash 999
+        let mut start_stop_daemon_location: String = String::new();
 
-update-alternatives --force --install /bin/dash dash /bin/bash 999
-" 2> /dev/stdout
+        if Path::new(&format!(
+            "{target_bootstrap_directory}/sbin/start-stop-daemon"
+        ))
+        .is_file()
+            == true
+        {
+            start_stop_daemon_location =
+                format!("{target_bootstrap_directory}/sbin/start-stop-daemon");
+        } else if Path::new(&format!(
+            "{target_bootstrap_directory}/usr/sbin/start-stop-daemon"
+        ))
+        .is_file()
+            == true
+        {
+            start_stop_daemon_location =
+                format!("{target_bootstrap_directory}/usr/sbin/start-stop-daemon");
+        };
+
+        let start_stop_daemon_location: String = start_stop_daemon_location;
+
+        print_message(
+            "debug",
+            &format!("renaming file: \"{start_stop_daemon_location}\" to \"{start_stop_daemon_location}.ORIGINAL\""),
+            &message_config,
+        );
+        if std::fs::rename(
+            format!("{start_stop_daemon_location}"),
+            format!("{start_stop_daemon_location}.ORIGINAL"),
         )
         .is_err()
             == true
         {
             print_message(
                 "error",
-                &format!("failed to create alternatives for: \"{target_bootstrap_directory}/bin/sh\" and \"{target_bootstrap_directory}/bin/dash\""),
+                &format!("failed to rename file: \"{start_stop_daemon_location}\""),
                 &message_config,
             );
 
@@ -3362,31 +5523,60 @@ update-alternatives --force --install /bin/dash dash /bin/bash 999
 
             return ExitCode::from(1);
         };
-    };
 
-    //////////////////////////////////////////////
+        print_message(
+            "debug",
+            &format!("creating temporary file: \"{start_stop_daemon_location}\""),
+            &message_config,
+        );
 
-    for directory in &all_package_sub_directories {
-        if Path::new(&format!("{all_packages_directory}/{directory}")).exists() == true {
-            print_message(
-                "debug",
-                &format!("installing all packages in: \"{all_packages_directory}/{directory}\""),
+        if create_file(
+            &format!("{start_stop_daemon_location}"),
+            "#! /bin/sh\n\necho -e '\\nWarning: Fake start-stop-daemon called, doing nothing'\n\nexit 0\n",
+            Some(0o755),
+            None,
+            &message_config,
+        ).is_err() == true {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
                 &message_config,
-            );
+            ).unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+
+        //////////////////////////////////////////////
+
+        if mount_virtual_kernel_file_systems(&target_bootstrap_directory, &message_config).is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
 
+        if target_package_set
+            .iter()
+            .any(|package| package.name == "dash")
+            == false
+        {
             if run_cmd!(
                 chroot "$target_bootstrap_directory" /usr/bin/env --ignore-environment bash -c "
 export HOME='/root'
 export TERM='$term_environment_variable'
 export PATH
-export DEBIAN_FRONTEND='$debian_frontend'
-export DEBCONF_NONINTERACTIVE_SEEN='$debconf_noninteractive_seen'
-export DEBCONF_NOWARNINGS='yes'
-export DPKG_COLORS='$colorful_dpkg'
 
-cd /packages/$directory
+update-alternatives --force --install /bin/sh sh /bin/bash 999
 
-dpkg --force-depends --force-confold --install *.deb
+update-alternatives --force --install /bin/dash dash /bin/bash 999
 " 2> /dev/stdout
             )
             .is_err()
@@ -3394,9 +5584,7 @@ dpkg --force-depends --force-confold --install *.deb
             {
                 print_message(
                     "error",
-                    &format!(
-                        "failed to install packages in: \"{all_packages_directory}/{directory}\""
-                    ),
+                    &format!("failed to create alternatives for: \"{target_bootstrap_directory}/bin/sh\" and \"{target_bootstrap_directory}/bin/dash\""),
                     &message_config,
                 );
 
@@ -3412,134 +5600,208 @@ dpkg --force-depends --force-confold --install *.deb
             };
         };
 
-        match &directory as &str {
-            "essential" => {
-                if essential_hooks.len() != 0 {
-                    run_hooks(
-                        "essential",
-                        &essential_hooks,
+        //////////////////////////////////////////////
+
+        for directory in &all_package_sub_directories {
+            if Path::new(&format!("{all_packages_directory}/{directory}")).exists() == true {
+                print_message(
+                    "debug",
+                    &format!(
+                        "installing all packages in: \"{all_packages_directory}/{directory}\""
+                    ),
+                    &message_config,
+                );
+
+                if run_cmd!(
+                    chroot "$target_bootstrap_directory" /usr/bin/env --ignore-environment bash -c "
+export HOME='/root'
+export TERM='$term_environment_variable'
+export PATH
+export DEBIAN_FRONTEND='$debian_frontend'
+export DEBCONF_NONINTERACTIVE_SEEN='$debconf_noninteractive_seen'
+export DEBCONF_NOWARNINGS='yes'
+export DPKG_COLORS='$colorful_dpkg'
+
+cd /packages/$directory
+
+dpkg --force-depends --force-confold --install *.deb
+" 2> /dev/stdout
+                )
+                .is_err()
+                    == true
+                {
+                    print_message(
+                        "error",
+                        &format!(
+                            "failed to install packages in: \"{all_packages_directory}/{directory}\""
+                        ),
+                        &message_config,
+                    );
+
+                    clean_up_on_exit(
                         &workspace_directory,
-                        Some(&all_packages_directory),
                         Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
                         &message_config,
-                    );
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
                 };
-            }
-            _ => {}
-        };
-    }
+            };
 
-    //////////////////////////////////////////////
+            match &directory as &str {
+                "essential" => {
+                    if target_oci_layers == "split" {
+                        match list_directory_tree(&target_bootstrap_directory, &message_config) {
+                            Ok(result) => essential_directory_tree = result,
+                            Err(..) => {
+                                clean_up_on_exit(
+                                    &workspace_directory,
+                                    Some(&target_bootstrap_directory),
+                                    &target_actions_to_skip,
+                                    &message_config,
+                                )
+                                .unwrap_or(());
 
-    if target_hooks.len() != 0 {
-        run_hooks(
-            "target",
-            &target_hooks,
-            &workspace_directory,
-            Some(&all_packages_directory),
-            Some(&target_bootstrap_directory),
-            &message_config,
-        );
-    };
+                                return ExitCode::from(1);
+                            }
+                        };
+                    };
 
-    //////////////////////////////////////////////
+                    if essential_hooks.len() != 0 {
+                        run_hooks(
+                            "essential",
+                            &essential_hooks,
+                            &workspace_directory,
+                            Some(&all_packages_directory),
+                            Some(&target_bootstrap_directory),
+                            &message_config,
+                        );
+                    };
+                }
+                _ => {}
+            };
+        }
 
-    if unmount_virtual_kernel_file_systems(&target_bootstrap_directory, &message_config).is_err()
-        == true
-    {
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+        //////////////////////////////////////////////
 
-        return ExitCode::from(1);
-    };
+        if target_hooks.len() != 0 {
+            run_hooks(
+                "target",
+                &target_hooks,
+                &workspace_directory,
+                Some(&all_packages_directory),
+                Some(&target_bootstrap_directory),
+                &message_config,
+            );
+        };
 
-    print_message(
-        "debug",
-        &format!("removing temporary file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""),
-        &message_config,
-    );
+        //////////////////////////////////////////////
+
+        if unmount_virtual_kernel_file_systems(&target_bootstrap_directory, &message_config)
+            .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
 
-    if std::fs::remove_file(format!("{target_bootstrap_directory}/usr/sbin/policy-rc.d")).is_err()
-        == true
-    {
         print_message(
-            "error",
+            "debug",
             &format!(
-                "failed to remove file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
+                "removing temporary file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
             ),
             &message_config,
         );
 
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+        if std::fs::remove_file(format!("{target_bootstrap_directory}/usr/sbin/policy-rc.d"))
+            .is_err()
+            == true
+        {
+            print_message(
+                "error",
+                &format!(
+                    "failed to remove file: \"{target_bootstrap_directory}/usr/sbin/policy-rc.d\""
+                ),
+                &message_config,
+            );
 
-        return ExitCode::from(1);
-    };
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
 
-    print_message(
-        "debug",
-        &format!("removing temporary file: \"{start_stop_daemon_location}\""),
-        &message_config,
-    );
+            return ExitCode::from(1);
+        };
 
-    if std::fs::remove_file(format!("{start_stop_daemon_location}")).is_err() == true {
         print_message(
-            "error",
-            &format!("failed to remove file: \"{start_stop_daemon_location}\""),
+            "debug",
+            &format!("removing temporary file: \"{start_stop_daemon_location}\""),
             &message_config,
         );
 
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
-        )
-        .unwrap_or(());
+        if std::fs::remove_file(format!("{start_stop_daemon_location}")).is_err() == true {
+            print_message(
+                "error",
+                &format!("failed to remove file: \"{start_stop_daemon_location}\""),
+                &message_config,
+            );
 
-        return ExitCode::from(1);
-    };
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
 
-    print_message(
-        "debug",
-        &format!("renaming file: \"{start_stop_daemon_location}.ORIGINAL\" to \"{start_stop_daemon_location}\""),
-        &message_config,
-    );
+            return ExitCode::from(1);
+        };
 
-    if std::fs::rename(
-        format!("{start_stop_daemon_location}.ORIGINAL"),
-        format!("{start_stop_daemon_location}"),
-    )
-    .is_err()
-        == true
-    {
         print_message(
-            "error",
-            &format!("failed to rename file: \"{start_stop_daemon_location}.ORIGINAL\""),
+            "debug",
+            &format!("renaming file: \"{start_stop_daemon_location}.ORIGINAL\" to \"{start_stop_daemon_location}\""),
             &message_config,
         );
 
-        clean_up_on_exit(
-            &workspace_directory,
-            Some(&target_bootstrap_directory),
-            &target_actions_to_skip,
-            &message_config,
+        if std::fs::rename(
+            format!("{start_stop_daemon_location}.ORIGINAL"),
+            format!("{start_stop_daemon_location}"),
         )
-        .unwrap_or(());
+        .is_err()
+            == true
+        {
+            print_message(
+                "error",
+                &format!("failed to rename file: \"{start_stop_daemon_location}.ORIGINAL\""),
+                &message_config,
+            );
 
-        return ExitCode::from(1);
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
     };
 
+    let essential_directory_tree: HashMap<String, String> = essential_directory_tree;
+
     //////////////////////////////////////////////
 
     if target_actions_to_skip.contains(&String::from("packages_removal")) == true {
@@ -3644,35 +5906,262 @@ dpkg --force-depends --force-confold --install *.deb
 
     //////////////////////////////////////////////
 
+    if target_license_report_file.is_empty() == false {
+        if create_license_report(
+            &target_bootstrap_directory,
+            &target_package_set,
+            &target_license_report_file,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
     match &target_output_format as &str {
         "tarball" => {
             println!("Packaging (this might take a while) ...");
 
-            print_message(
-                "debug",
-                &format!(
-                    "creating tarball: \"{target_output_directory}/{target_output_file_name}.tar\""
-                ),
+            if create_tarball(
+                &target_bootstrap_directory,
+                &format!("{target_output_directory}/{target_output_file_name}{output_file_suffix}"),
+                &None,
                 &message_config,
-            );
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
 
-            if run_cmd!(
-                bash -c "
-                    cd '$target_bootstrap_directory'
-                    tar --create --file='$target_output_directory/$target_output_file_name.tar' *
-                " 2> /dev/stdout
+                return ExitCode::from(1);
+            };
+        }
+        "squashfs" => {
+            println!("Packaging (this might take a while) ...");
+
+            if create_squashfs_image(
+                &target_bootstrap_directory,
+                &format!("{target_output_directory}/{target_output_file_name}{output_file_suffix}"),
+                &target_compression,
+                &source_date_epoch,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        }
+        "ext4" | "img" => {
+            println!("Packaging (this might take a while) ...");
+
+            let partition_type: Option<String>;
+
+            match &target_output_format as &str {
+                "img" => {
+                    partition_type = Some(default_root_partition_type(&primary_architecture));
+                }
+                _ => partition_type = None,
+            };
+
+            let image_size: u64;
+
+            match calculate_image_size(
+                &target_bootstrap_directory,
+                &target_image_headroom_percentage,
+                &target_image_headroom_size,
+                &message_config,
+            ) {
+                Ok(result) => image_size = result,
+                Err(..) => {
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                }
+            };
+
+            if create_ext4_image(
+                &target_bootstrap_directory,
+                &format!("{target_output_directory}/{target_output_file_name}{output_file_suffix}"),
+                &image_size,
+                &target_filesystem_uuid,
+                &partition_type,
+                &source_date_epoch,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        }
+        "oci" | "oci-archive" => {
+            println!("Packaging (this might take a while) ...");
+            let mut layers: Vec<(Option<Vec<String>>, Vec<String>)> = Vec::new();
+
+            if target_oci_layers == "split" && essential_directory_tree.is_empty() == false {
+                match list_directory_tree(&target_bootstrap_directory, &message_config) {
+                    Ok(result) => {
+                        let (essential_paths, remaining_paths, whiteout_paths) =
+                            split_directory_tree(&essential_directory_tree, &result);
+
+                        layers.push((Some(essential_paths), Vec::new()));
+
+                        if remaining_paths.len() != 0 || whiteout_paths.len() != 0 {
+                            layers.push((Some(remaining_paths), whiteout_paths));
+                        };
+                    }
+                    Err(..) => {
+                        clean_up_on_exit(
+                            &workspace_directory,
+                            Some(&target_bootstrap_directory),
+                            &target_actions_to_skip,
+                            &message_config,
+                        )
+                        .unwrap_or(());
+
+                        return ExitCode::from(1);
+                    }
+                };
+            } else {
+                if target_oci_layers == "split" {
+                    print_message(
+                        "warning",
+                        "no essential packages were installed, creating a single layer.",
+                        &message_config,
+                    );
+                };
+
+                layers.push((None, Vec::new()));
+            };
+
+            let layers: Vec<(Option<Vec<String>>, Vec<String>)> = layers;
+
+            if create_oci_image(
+                &target_bootstrap_directory,
+                &format!("{target_output_directory}/{target_output_file_name}{output_file_suffix}"),
+                &(target_output_format == "oci-archive"),
+                &layers,
+                &primary_architecture,
+                &primary_suite,
+                &workspace_directory,
+                &source_date_epoch,
+                &message_config,
             )
             .is_err()
                 == true
             {
+                clean_up_on_exit(
+                    &workspace_directory,
+                    Some(&target_bootstrap_directory),
+                    &target_actions_to_skip,
+                    &message_config,
+                )
+                .unwrap_or(());
+
+                return ExitCode::from(1);
+            };
+        }
+        "cpio" => {
+            println!("Packaging (this might take a while) ...");
+
+            if std::fs::symlink_metadata(format!("{target_bootstrap_directory}/init")).is_err()
+                == true
+            {
+                let init_location: &str;
+
+                // the kernel runs '/init' from an initramfs, so point it at the init system if one was
+                // installed or else at a shell.
+                if Path::new(&format!("{target_bootstrap_directory}/sbin/init")).exists() == true {
+                    init_location = "sbin/init";
+                } else {
+                    init_location = "bin/sh";
+                };
+
                 print_message(
-                    "error",
-                    &format!(
-                        "failed to create tarball: \"{target_output_directory}/{target_output_file_name}.tar\""
-                    ),
+                    "debug",
+                    &format!("creating symbolic link: \"{target_bootstrap_directory}/init\" to \"{init_location}\""),
                     &message_config,
                 );
 
+                if std::os::unix::fs::symlink(
+                    init_location,
+                    format!("{target_bootstrap_directory}/init"),
+                )
+                .is_err()
+                    == true
+                {
+                    print_message(
+                        "error",
+                        &format!(
+                            "failed to create symbolic link: \"{target_bootstrap_directory}/init\""
+                        ),
+                        &message_config,
+                    );
+
+                    clean_up_on_exit(
+                        &workspace_directory,
+                        Some(&target_bootstrap_directory),
+                        &target_actions_to_skip,
+                        &message_config,
+                    )
+                    .unwrap_or(());
+
+                    return ExitCode::from(1);
+                };
+            };
+
+            if create_cpio_archive(
+                &target_bootstrap_directory,
+                &format!("{target_output_directory}/{target_output_file_name}{output_file_suffix}"),
+                &target_compression,
+                &workspace_directory,
+                &source_date_epoch,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
                 clean_up_on_exit(
                     &workspace_directory,
                     Some(&target_bootstrap_directory),
@@ -3687,6 +6176,53 @@ dpkg --force-depends --force-confold --install *.deb
         _ => {}
     };
 
+    if download_sources == true {
+        if bundle_source_packages(
+            &workspace_sources_directory,
+            &target_sources_directory,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    if target_manifest_file.is_empty() == false {
+        if create_manifest(
+            &target_package_set,
+            &target_manifest_file,
+            &target_manifest_format,
+            DEBSTRAP_VERSION,
+            &source_date_epoch,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            clean_up_on_exit(
+                &workspace_directory,
+                Some(&target_bootstrap_directory),
+                &target_actions_to_skip,
+                &message_config,
+            )
+            .unwrap_or(());
+
+            return ExitCode::from(1);
+        };
+    };
+
+    //////////////////////////////////////////////
+
     println!("Bootstrap complete.");
 
     if clean_up_on_exit(
@@ -0,0 +1,254 @@
+use crate::defaults::*;
+use crate::functions::*;
+use crate::package::*;
+
+fn package_uri(package: &Package) -> String {
+    return format!(
+        "{}{}/{}",
+        package.origin_uri_scheme, package.origin_uri_path, package.file_name
+    );
+}
+
+fn package_url(package: &Package) -> String {
+    let mut distribution: String = default_distribution_name(&package.origin_suite);
+
+    if distribution.is_empty() == true {
+        distribution = String::from("debian");
+    };
+
+    // the version has to be percent-encoded since it can contain an epoch (':') and '+'.
+    let mut encoded_version: String = String::new();
+
+    for character in package.version.chars() {
+        match character {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '-' | '_' | '~' => {
+                encoded_version.push(character);
+            }
+            _ => encoded_version.push_str(&format!("%{:02X}", character as u32)),
+        };
+    }
+
+    return format!(
+        "pkg:deb/{distribution}/{}@{encoded_version}?arch={}&distro={}",
+        package.name, package.architecture, package.origin_suite
+    );
+}
+
+fn spdx_identifier(package: &Package) -> String {
+    return format!("SPDXRef-Package-{}-{}", package.name, package.architecture)
+        .chars()
+        .map(|character| match character {
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '.' | '-' => character,
+            _ => '-',
+        })
+        .collect::<String>();
+}
+
+fn manifest_uuid(packages: &Vec<Package>) -> String {
+    // the UUID is derived from the package set so the same bootstrap produces the same manifest.
+    let mut seed: String = String::new();
+
+    for package in packages {
+        seed.push_str(&format!(
+            "{} {} {}\n",
+            package.name, package.architecture, package.version
+        ));
+    }
+
+    return derive_uuid(&seed);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_manifest(
+    target_package_set: &Vec<Package>,
+    output_file: &str,
+    manifest_format: &str,
+    tool_version: &str,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating {manifest_format} manifest: \"{output_file}\""),
+        &message_config,
+    );
+
+    let mut packages: Vec<Package> = target_package_set.clone();
+
+    packages.sort_unstable_by(|a, b| {
+        (&a.name, &a.architecture, &a.version).cmp(&(&b.name, &b.architecture, &b.version))
+    });
+
+    let packages: Vec<Package> = packages;
+
+    let mut manifest: String = String::new();
+
+    match manifest_format {
+        "text" => {
+            for package in &packages {
+                manifest.push_str(&format!(
+                    "\
+Package: {}
+Version: {}
+Architecture: {}
+Source: {} ({})
+Suite: {}
+Component: {}
+URI: {}
+SHA256: {}
+Installed-Size: {}
+
+",
+                    package.name,
+                    package.version,
+                    package.architecture,
+                    package.source,
+                    package.source_version,
+                    package.origin_suite,
+                    package.origin_component,
+                    package_uri(&package),
+                    package.sha256_hash,
+                    package.installed_size,
+                ));
+            }
+        }
+        "json" => {
+            let mut entries: Vec<String> = Vec::new();
+
+            for package in &packages {
+                entries.push(format!(
+                    "    {{\"name\": \"{}\", \"version\": \"{}\", \"architecture\": \"{}\", \"source\": \"{}\", \"source_version\": \"{}\", \"origin\": {{\"suite\": \"{}\", \"component\": \"{}\", \"uri\": \"{}\"}}, \"sha256\": \"{}\", \"installed_size_kib\": {}}}",
+                    escape_json_string(&package.name),
+                    escape_json_string(&package.version),
+                    escape_json_string(&package.architecture),
+                    escape_json_string(&package.source),
+                    escape_json_string(&package.source_version),
+                    escape_json_string(&package.origin_suite),
+                    escape_json_string(&package.origin_component),
+                    escape_json_string(&package_uri(&package)),
+                    escape_json_string(&package.sha256_hash),
+                    package.installed_size,
+                ));
+            }
+
+            manifest = format!("{{\n  \"packages\": [\n{}\n  ]\n}}\n", entries.join(",\n"));
+        }
+        "spdx" => {
+            let created: String;
+
+            match get_utc_timestamp(&source_date_epoch, &message_config) {
+                Ok(result) => created = result,
+                Err(..) => return Err(()),
+            };
+
+            let mut entries: Vec<String> = Vec::new();
+            let mut relationships: Vec<String> = Vec::new();
+
+            for package in &packages {
+                entries.push(format!(
+                    "    {{\"SPDXID\": \"{}\", \"name\": \"{}\", \"versionInfo\": \"{}\", \"supplier\": \"NOASSERTION\", \"downloadLocation\": \"{}\", \"filesAnalyzed\": false, \"checksums\": [{{\"algorithm\": \"SHA256\", \"checksumValue\": \"{}\"}}], \"sourceInfo\": \"built from source package: {} {}\", \"licenseConcluded\": \"NOASSERTION\", \"licenseDeclared\": \"NOASSERTION\", \"copyrightText\": \"NOASSERTION\", \"externalRefs\": [{{\"referenceCategory\": \"PACKAGE-MANAGER\", \"referenceType\": \"purl\", \"referenceLocator\": \"{}\"}}]}}",
+                    spdx_identifier(&package),
+                    escape_json_string(&package.name),
+                    escape_json_string(&package.version),
+                    escape_json_string(&package_uri(&package)),
+                    escape_json_string(&package.sha256_hash),
+                    escape_json_string(&package.source),
+                    escape_json_string(&package.source_version),
+                    escape_json_string(&package_url(&package)),
+                ));
+
+                relationships.push(format!(
+                    "    {{\"spdxElementId\": \"SPDXRef-DOCUMENT\", \"relationshipType\": \"DESCRIBES\", \"relatedSpdxElement\": \"{}\"}}",
+                    spdx_identifier(&package),
+                ));
+            }
+
+            manifest = format!(
+                "\
+{{
+  \"spdxVersion\": \"SPDX-2.3\",
+  \"dataLicense\": \"CC0-1.0\",
+  \"SPDXID\": \"SPDXRef-DOCUMENT\",
+  \"name\": \"{}\",
+  \"documentNamespace\": \"urn:uuid:{}\",
+  \"creationInfo\": {{\"created\": \"{created}\", \"creators\": [\"Tool: debstrap-{tool_version}\"]}},
+  \"packages\": [
+{}
+  ],
+  \"relationships\": [
+{}
+  ]
+}}
+",
+                escape_json_string(
+                    &std::path::Path::new(output_file)
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                ),
+                manifest_uuid(&packages),
+                entries.join(",\n"),
+                relationships.join(",\n"),
+            );
+        }
+        "cyclonedx" => {
+            let created: String;
+
+            match get_utc_timestamp(&source_date_epoch, &message_config) {
+                Ok(result) => created = result,
+                Err(..) => return Err(()),
+            };
+
+            let mut entries: Vec<String> = Vec::new();
+
+            for package in &packages {
+                entries.push(format!(
+                    "    {{\"type\": \"library\", \"bom-ref\": \"{}\", \"name\": \"{}\", \"version\": \"{}\", \"purl\": \"{}\", \"hashes\": [{{\"alg\": \"SHA-256\", \"content\": \"{}\"}}], \"externalReferences\": [{{\"type\": \"distribution\", \"url\": \"{}\"}}], \"properties\": [{{\"name\": \"debian:source\", \"value\": \"{}\"}}, {{\"name\": \"debian:source_version\", \"value\": \"{}\"}}, {{\"name\": \"debian:suite\", \"value\": \"{}\"}}, {{\"name\": \"debian:component\", \"value\": \"{}\"}}, {{\"name\": \"debian:installed_size_kib\", \"value\": \"{}\"}}]}}",
+                    escape_json_string(&package_url(&package)),
+                    escape_json_string(&package.name),
+                    escape_json_string(&package.version),
+                    escape_json_string(&package_url(&package)),
+                    escape_json_string(&package.sha256_hash),
+                    escape_json_string(&package_uri(&package)),
+                    escape_json_string(&package.source),
+                    escape_json_string(&package.source_version),
+                    escape_json_string(&package.origin_suite),
+                    escape_json_string(&package.origin_component),
+                    package.installed_size,
+                ));
+            }
+
+            manifest = format!(
+                "\
+{{
+  \"bomFormat\": \"CycloneDX\",
+  \"specVersion\": \"1.5\",
+  \"serialNumber\": \"urn:uuid:{}\",
+  \"version\": 1,
+  \"metadata\": {{\"timestamp\": \"{created}\", \"tools\": {{\"components\": [{{\"type\": \"application\", \"name\": \"debstrap\", \"version\": \"{tool_version}\"}}]}}}},
+  \"components\": [
+{}
+  ]
+}}
+",
+                manifest_uuid(&packages),
+                entries.join(",\n"),
+            );
+        }
+        _ => {
+            print_message(
+                "error",
+                &format!("unrecognized manifest format: \"{manifest_format}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    if create_file(output_file, &manifest, None, None, &message_config).is_err() == true {
+        return Err(());
+    };
+
+    return Ok(());
+}
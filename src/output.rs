@@ -0,0 +1,1209 @@
+use crate::functions::*;
+
+use cmd_lib::run_cmd;
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+
+pub fn create_squashfs_image(
+    input_directory: &str,
+    output_file: &str,
+    compression: &str,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating squashfs image: \"{output_file}\" using compression: \"{compression}\""),
+        &message_config,
+    );
+
+    // mksquashfs always sorts the directory entries it reads, the options below only remove the
+    // remaining sources of variance (timestamps) when a reproducible build was requested.
+
+    let mut reproducible_arguments: Vec<String> = Vec::new();
+
+    match source_date_epoch {
+        Some(epoch) => {
+            print_message(
+                "debug",
+                &format!("using timestamp: \"{epoch}\" for all files in: \"{output_file}\""),
+                &message_config,
+            );
+
+            reproducible_arguments = Vec::from([
+                String::from("-reproducible"),
+                String::from("-mkfs-time"),
+                epoch.to_string(),
+                String::from("-all-time"),
+                epoch.to_string(),
+            ]);
+        }
+        None => {}
+    };
+
+    if run_cmd!(
+        mksquashfs "$input_directory" "$output_file" -noappend -no-progress -comp "$compression" $[reproducible_arguments] 2> /dev/stdout
+    )
+    .is_err()
+        == true
+    {
+        print_message(
+            "error",
+            &format!("failed to create squashfs image: \"{output_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// the size of the contents is taken from the target directory itself, rounded up to whole blocks,
+// and the space that ext4 needs for its own metadata is added on top of the headroom.
+pub fn calculate_image_size(
+    input_directory: &str,
+    headroom_percentage: &u64,
+    headroom_size: &u64,
+    message_config: &MessageConfig,
+) -> Result<u64, ()> {
+    const MEBIBYTE: u64 = 1024 * 1024;
+    const BLOCK_SIZE: u64 = 4096;
+    const BYTES_PER_INODE: u64 = 16384;
+
+    let directory_tree: HashMap<String, String>;
+
+    match list_directory_tree(input_directory, &message_config) {
+        Ok(result) => directory_tree = result,
+        Err(..) => return Err(()),
+    };
+
+    // the size is the fifth part of the signature of every path.
+    let content_size: u64 = directory_tree
+        .values()
+        .map(|signature| {
+            signature
+                .split_whitespace()
+                .nth(4)
+                .unwrap_or_default()
+                .parse::<u64>()
+                .unwrap_or(0)
+                .div_ceil(BLOCK_SIZE)
+                * BLOCK_SIZE
+        })
+        .sum::<u64>();
+
+    let amount_of_inodes: u64 = directory_tree.len() as u64;
+
+    let mut image_size: u64 =
+        content_size + (content_size * headroom_percentage / 100) + headroom_size;
+
+    // mke2fs creates one inode for every 16KiB (or less on small filesystems), so the filesystem has
+    // to be large enough to get an inode for every path plus the reserved ones.
+    image_size = std::cmp::max(image_size, (amount_of_inodes + 16) * BYTES_PER_INODE);
+
+    // inode tables of up to 256 bytes for every 4KiB, and the 5% of blocks reserved for root.
+    image_size += image_size / 16;
+    image_size += image_size * 5 / 95;
+
+    // the default journal size of mke2fs for a filesystem of this size.
+    let journal_size: u64 = match image_size / MEBIBYTE {
+        0..128 => 4 * MEBIBYTE,
+        128..1024 => 16 * MEBIBYTE,
+        1024..2048 => 32 * MEBIBYTE,
+        2048..16384 => 64 * MEBIBYTE,
+        16384..32768 => 128 * MEBIBYTE,
+        32768..65536 => 256 * MEBIBYTE,
+        65536..131072 => 512 * MEBIBYTE,
+        _ => 1024 * MEBIBYTE,
+    };
+
+    // the superblock and its backups, the group descriptors, and the bitmaps.
+    image_size += journal_size + (image_size / 100) + (4 * MEBIBYTE);
+
+    // round up to the next whole mebibyte so the filesystem (and partition) stay aligned.
+    if image_size % MEBIBYTE != 0 {
+        image_size += MEBIBYTE - (image_size % MEBIBYTE);
+    };
+
+    print_message(
+        "debug",
+        &format!("image size: \"{image_size}\" bytes for contents of: \"{content_size}\" bytes in: \"{amount_of_inodes}\" inodes"),
+        &message_config,
+    );
+
+    return Ok(image_size);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_ext4_image(
+    input_directory: &str,
+    output_file: &str,
+    filesystem_size: &u64,
+    filesystem_uuid: &str,
+    partition_type: &Option<String>,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    const MEBIBYTE: u64 = 1024 * 1024;
+    const SECTOR_SIZE: u64 = 512;
+
+    // the filesystem starts at 1MiB inside of a partitioned image and 1MiB is left free at the end
+    // for the backup GPT header.
+    let filesystem_offset: u64;
+    let image_size: u64;
+
+    match partition_type {
+        Some(..) => {
+            filesystem_offset = MEBIBYTE;
+            image_size = filesystem_size + (2 * MEBIBYTE);
+        }
+        None => {
+            filesystem_offset = 0;
+            image_size = *filesystem_size;
+        }
+    };
+
+    print_message(
+        "debug",
+        &format!("creating image: \"{output_file}\" of size: \"{image_size}\" bytes"),
+        &message_config,
+    );
+
+    if run_cmd!(truncate --size="$image_size" "$output_file" 2> /dev/stdout).is_err() == true {
+        print_message(
+            "error",
+            &format!("failed to create image: \"{output_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    match partition_type {
+        Some(partition_type) => {
+            let mut partition_table: String = String::from("label: gpt\n");
+
+            // sfdisk picks random GUIDs for the disk and the partition unless they are given.
+            let mut partition_uuid: String = String::new();
+
+            match source_date_epoch {
+                Some(..) => {
+                    partition_table.push_str(&format!(
+                        "label-id: {}\n",
+                        derive_uuid(&format!("disk {filesystem_uuid}"))
+                    ));
+                    partition_uuid = format!(
+                        ", uuid={}",
+                        derive_uuid(&format!("partition {filesystem_uuid}"))
+                    );
+                }
+                None => {}
+            };
+
+            partition_table.push_str(&format!(
+                "start={}, size={}, type={partition_type}{partition_uuid}, name=root\n",
+                filesystem_offset / SECTOR_SIZE,
+                filesystem_size / SECTOR_SIZE,
+            ));
+
+            print_message(
+                "debug",
+                &format!("creating GPT partition table in: \"{output_file}\""),
+                &message_config,
+            );
+
+            if run_cmd!(
+                echo -n "$partition_table" | sfdisk --quiet --no-reread --no-tell-kernel "$output_file" 2> /dev/stdout
+            )
+            .is_err()
+                == true
+            {
+                print_message(
+                    "error",
+                    &format!("failed to create GPT partition table in: \"{output_file}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+        }
+        None => {}
+    };
+
+    print_message(
+        "debug",
+        &format!("creating ext4 filesystem with UUID: \"{filesystem_uuid}\" in: \"{output_file}\""),
+        &message_config,
+    );
+
+    // mke2fs copies the contents of the input directory (including ownership, permissions, and
+    // special files) straight into the filesystem so no loop device or mount is needed.
+    let filesystem_size_in_kibibytes: String = format!("{}k", filesystem_size / 1024);
+    let extended_options: String =
+        format!("offset={filesystem_offset},hash_seed={filesystem_uuid}");
+
+    // mke2fs uses this instead of the current time for the timestamps of the filesystem.
+    let mut environment: Vec<String> = Vec::new();
+
+    match source_date_epoch {
+        Some(epoch) => environment.push(format!("E2FSPROGS_FAKE_TIME={epoch}")),
+        None => {}
+    };
+
+    if run_cmd!(
+        env $[environment] mke2fs -q -F -t ext4 -d "$input_directory" -U "$filesystem_uuid" -E "$extended_options" "$output_file" "$filesystem_size_in_kibibytes" 2> /dev/stdout
+    )
+    .is_err()
+        == true
+    {
+        print_message(
+            "error",
+            &format!("failed to create ext4 filesystem in: \"{output_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_tarball(
+    input_directory: &str,
+    output_file: &str,
+    file_list: &Option<Vec<String>>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating tarball: \"{output_file}\""),
+        &message_config,
+    );
+
+    // without a file list every top-level entry that does not start with a dot is added, the same
+    // way "tar --create *" would inside the input directory.
+    match file_list {
+        Some(..) => {
+            return run_tar(
+                input_directory,
+                output_file,
+                file_list,
+                &Vec::new(),
+                &message_config,
+            );
+        }
+        None => {
+            let mut top_level_entries: Vec<String> = match std::fs::read_dir(input_directory) {
+                Ok(result) => result
+                    .filter_map(|element| element.ok())
+                    .map(|element| String::from(element.file_name().to_string_lossy()))
+                    .filter(|file_name| file_name.starts_with('.') == false)
+                    .collect::<Vec<String>>(),
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("failed to read directory: \"{input_directory}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                }
+            };
+
+            top_level_entries.sort_unstable();
+
+            return run_tar(
+                input_directory,
+                output_file,
+                &None,
+                &top_level_entries,
+                &message_config,
+            );
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// layers are sorted by name and stored with numeric ownership so the same input always produces the
+// same digest regardless of the host's user database.
+fn create_layer_tarball(
+    input_directory: &str,
+    output_file: &str,
+    file_list: &Option<Vec<String>>,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating layer tarball: \"{output_file}\""),
+        &message_config,
+    );
+
+    let mut tar_arguments: Vec<String> =
+        Vec::from([String::from("--sort=name"), String::from("--numeric-owner")]);
+
+    match source_date_epoch {
+        Some(epoch) => {
+            tar_arguments.push(format!("--mtime=@{epoch}"));
+            tar_arguments.push(String::from("--clamp-mtime"));
+        }
+        None => {}
+    };
+
+    if file_list.is_none() == true {
+        tar_arguments.push(String::from("."));
+    };
+
+    return run_tar(
+        input_directory,
+        output_file,
+        file_list,
+        &tar_arguments,
+        &message_config,
+    );
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// a file list is passed to tar through a file so it is not limited by the length of the command
+// line, and only the listed paths are added.
+fn run_tar(
+    input_directory: &str,
+    output_file: &str,
+    file_list: &Option<Vec<String>>,
+    extra_arguments: &Vec<String>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let mut tar_arguments: Vec<String> = Vec::from([
+        String::from("--create"),
+        format!("--file={output_file}"),
+        format!("--directory={input_directory}"),
+    ]);
+
+    let file_list_location: String = format!("{output_file}.list");
+
+    match file_list {
+        Some(file_list) => {
+            let mut file_list_contents: String = String::new();
+
+            for file in file_list {
+                file_list_contents.push_str(&format!("{file}\0"));
+            }
+
+            if create_file(
+                &file_list_location,
+                &file_list_contents,
+                None,
+                None,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                return Err(());
+            };
+
+            tar_arguments.push(String::from("--null"));
+            tar_arguments.push(String::from("--no-recursion"));
+            tar_arguments.push(format!("--files-from={file_list_location}"));
+        }
+        None => {}
+    };
+
+    tar_arguments.extend(extra_arguments.iter().cloned());
+
+    if run_cmd!(tar $[tar_arguments] 2> /dev/stdout).is_err() == true {
+        print_message(
+            "error",
+            &format!("failed to create tarball: \"{output_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    if file_list.is_some() == true {
+        if std::fs::remove_file(&file_list_location).is_err() == true {
+            print_message(
+                "error",
+                &format!("failed to remove file: \"{file_list_location}\""),
+                &message_config,
+            );
+            return Err(());
+        };
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn list_directory_tree(
+    input_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<HashMap<String, String>, ()> {
+    print_message(
+        "debug",
+        &format!("listing the contents of directory: \"{input_directory}\""),
+        &message_config,
+    );
+
+    // every path (relative to the input directory) is mapped to a signature of its metadata which
+    // is enough to tell if it changed between two listings.
+    fn signature_of(metadata: &std::fs::Metadata) -> String {
+        let file_type: char;
+
+        if metadata.is_dir() == true {
+            file_type = 'd';
+        } else if metadata.is_symlink() == true {
+            file_type = 'l';
+        } else if metadata.is_file() == true {
+            file_type = 'f';
+        } else {
+            file_type = 's';
+        };
+
+        return format!(
+            "{file_type} {:o} {} {} {} {}.{} {}",
+            metadata.mode(),
+            metadata.uid(),
+            metadata.gid(),
+            metadata.size(),
+            metadata.mtime(),
+            metadata.mtime_nsec(),
+            metadata.rdev(),
+        );
+    }
+
+    let root_metadata: std::fs::Metadata;
+
+    match std::fs::symlink_metadata(input_directory) {
+        Ok(result) => root_metadata = result,
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read metadata of directory: \"{input_directory}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    let mut directory_tree: HashMap<String, String> = HashMap::new();
+
+    directory_tree.insert(String::from("."), signature_of(&root_metadata));
+
+    let mut directories_to_list: Vec<String> = Vec::from([String::from(".")]);
+
+    while let Some(directory) = directories_to_list.pop() {
+        let directory_contents: std::fs::ReadDir;
+
+        match std::fs::read_dir(format!("{input_directory}/{directory}")) {
+            Ok(result) => directory_contents = result,
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("failed to read directory: \"{input_directory}/{directory}\""),
+                    &message_config,
+                );
+                return Err(());
+            }
+        };
+
+        for entry in directory_contents {
+            let element: std::fs::DirEntry;
+
+            match entry {
+                Ok(result) => element = result,
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("failed to read directory: \"{input_directory}/{directory}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                }
+            };
+
+            let path: String = format!("{directory}/{}", element.file_name().to_string_lossy());
+
+            let metadata: std::fs::Metadata;
+
+            match element.metadata() {
+                Ok(result) => metadata = result,
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("failed to read metadata of: \"{input_directory}/{path}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                }
+            };
+
+            // don't descend into anything mounted inside of the directory (/proc, /sys, etc.).
+            if metadata.is_dir() == true && metadata.dev() == root_metadata.dev() {
+                directories_to_list.push(path.clone());
+            };
+
+            directory_tree.insert(path, signature_of(&metadata));
+        }
+    }
+
+    return Ok(directory_tree);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// returns the paths of the lower and the upper layer, and the whiteouts of the upper layer.
+pub fn split_directory_tree(
+    earlier_tree: &HashMap<String, String>,
+    current_tree: &HashMap<String, String>,
+) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let mut lower_paths: Vec<String> = Vec::new();
+    let mut upper_paths: Vec<String> = Vec::new();
+    let mut whiteout_paths: Vec<String> = Vec::new();
+
+    // paths that are unchanged since the earlier listing go in the lower layer, everything new or
+    // changed goes in the upper layer. directories that changed are kept in both so the lower layer
+    // stays complete on its own.
+    for (path, signature) in current_tree {
+        match earlier_tree.get(path) {
+            Some(earlier_signature) if earlier_signature == signature => {
+                lower_paths.push(path.clone());
+            }
+            Some(..) if signature.starts_with("d ") == true => {
+                lower_paths.push(path.clone());
+                upper_paths.push(path.clone());
+            }
+            _ => {
+                upper_paths.push(path.clone());
+            }
+        };
+    }
+
+    //////////////////////////////////////
+
+    // a path that was removed since the earlier listing, or that changed between a directory and
+    // something else, is hidden from the lower layer with an OCI whiteout in the upper layer.
+    let is_removed = |path: &str| -> bool {
+        match (earlier_tree.get(path), current_tree.get(path)) {
+            (Some(..), None) => return true,
+            (Some(earlier_signature), Some(signature)) => {
+                return earlier_signature.starts_with("d ") != signature.starts_with("d ");
+            }
+            _ => return false,
+        };
+    };
+
+    // a directory that still exists but lost all of its earlier contents gets an opaque whiteout
+    // instead of one whiteout per entry.
+    let mut earlier_children: HashMap<&str, (usize, usize)> = HashMap::new();
+
+    for path in earlier_tree.keys() {
+        match path.rsplit_once('/') {
+            Some((parent, _)) => {
+                let (total, missing) = earlier_children.entry(parent).or_insert((0, 0));
+
+                *total += 1;
+
+                if current_tree.contains_key(path) == false {
+                    *missing += 1;
+                };
+            }
+            None => {}
+        };
+    }
+
+    let is_opaque = |directory: &str| -> bool {
+        return is_removed(directory) == false
+            && current_tree.contains_key(directory) == true
+            && earlier_children
+                .get(directory)
+                .is_some_and(|(total, missing)| total == missing);
+    };
+
+    for directory in earlier_children.keys() {
+        if is_opaque(directory) == true {
+            whiteout_paths.push(format!("{directory}/.wh..wh..opq"));
+        };
+    }
+
+    for path in earlier_tree.keys() {
+        if is_removed(path) == false {
+            continue;
+        };
+
+        let (parent, name) = match path.rsplit_once('/') {
+            Some(result) => result,
+            None => continue,
+        };
+
+        // everything below a removed or opaque directory is already hidden.
+        let mut ancestor: &str = parent;
+        let mut is_hidden: bool = is_opaque(parent);
+
+        while let Some((next_ancestor, _)) = ancestor.rsplit_once('/') {
+            if is_removed(ancestor) == true {
+                is_hidden = true;
+                break;
+            };
+
+            ancestor = next_ancestor;
+        }
+
+        if is_hidden == false {
+            whiteout_paths.push(format!("{parent}/.wh.{name}"));
+        };
+    }
+
+    // sorted by path components, the same order "tar --sort=name" uses for whole directories.
+    lower_paths.sort_unstable_by(|a, b| a.split('/').cmp(b.split('/')));
+    upper_paths.sort_unstable_by(|a, b| a.split('/').cmp(b.split('/')));
+    whiteout_paths.sort_unstable_by(|a, b| a.split('/').cmp(b.split('/')));
+
+    return (lower_paths, upper_paths, whiteout_paths);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// the whiteouts are written as empty files before the contents of the layer, as the OCI image spec
+// asks for them to come before their siblings.
+fn create_layer_with_whiteouts(
+    input_directory: &str,
+    layer_file: &str,
+    file_list: &Option<Vec<String>>,
+    whiteout_paths: &Vec<String>,
+    workspace_directory: &str,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let whiteouts_directory: String = format!("{workspace_directory}/whiteouts");
+
+    for whiteout in whiteout_paths {
+        print_message(
+            "debug",
+            &format!("adding whiteout: \"{whiteout}\""),
+            &message_config,
+        );
+
+        let whiteout_parent: &str = match whiteout.rsplit_once('/') {
+            Some((parent, _)) => parent,
+            None => ".",
+        };
+
+        if std::fs::create_dir_all(format!("{whiteouts_directory}/{whiteout_parent}")).is_err()
+            == true
+        {
+            print_message(
+                "error",
+                &format!("failed to create directory: \"{whiteouts_directory}/{whiteout_parent}\""),
+                &message_config,
+            );
+            return Err(());
+        };
+
+        if create_file(
+            &format!("{whiteouts_directory}/{whiteout}"),
+            "",
+            None,
+            None,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            return Err(());
+        };
+    }
+
+    if create_layer_tarball(
+        &whiteouts_directory,
+        layer_file,
+        &Some(whiteout_paths.clone()),
+        &source_date_epoch,
+        &message_config,
+    )
+    .is_err()
+        == true
+        || create_layer_tarball(
+            input_directory,
+            &format!("{layer_file}.contents"),
+            file_list,
+            &source_date_epoch,
+            &message_config,
+        )
+        .is_err()
+            == true
+    {
+        return Err(());
+    };
+
+    if run_cmd!(tar --concatenate --file="$layer_file" "$layer_file.contents" 2> /dev/stdout)
+        .is_err()
+        == true
+    {
+        print_message(
+            "error",
+            &format!("failed to create layer: \"{layer_file}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    if std::fs::remove_file(format!("{layer_file}.contents")).is_err() == true {
+        print_message(
+            "error",
+            &format!("failed to remove file: \"{layer_file}.contents\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    if remove_directory(&whiteouts_directory, &message_config).is_err() == true {
+        return Err(());
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// every layer is a list of paths to include (or everything) and a list of whiteouts that hide
+// paths of the layers below it.
+pub fn create_oci_image(
+    input_directory: &str,
+    output_location: &str,
+    create_archive: &bool,
+    layers: &Vec<(Option<Vec<String>>, Vec<String>)>,
+    architecture: &str,
+    reference_name: &str,
+    workspace_directory: &str,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    use sha2::{Digest, Sha256};
+
+    const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+    const CONFIG_MEDIA_TYPE: &str = "application/vnd.oci.image.config.v1+json";
+    const LAYER_MEDIA_TYPE: &str = "application/vnd.oci.image.layer.v1.tar+gzip";
+
+    let layout_directory: String;
+
+    match create_archive {
+        true => layout_directory = format!("{workspace_directory}/oci"),
+        false => layout_directory = String::from(output_location),
+    };
+
+    let blobs_directory: String = format!("{layout_directory}/blobs/sha256");
+
+    print_message(
+        "debug",
+        &format!("creating OCI image layout: \"{layout_directory}\""),
+        &message_config,
+    );
+
+    for directory in [
+        layout_directory.clone(),
+        format!("{layout_directory}/blobs"),
+        blobs_directory.clone(),
+    ] {
+        if create_directory(&directory, &message_config).is_err() == true {
+            return Err(());
+        };
+    }
+
+    let created: String;
+
+    match get_utc_timestamp(&source_date_epoch, &message_config) {
+        Ok(result) => created = result,
+        Err(..) => return Err(()),
+    };
+
+    //////////////////////////////////////////////
+
+    let mut diff_ids: Vec<String> = Vec::new();
+    let mut layer_descriptors: Vec<String> = Vec::new();
+
+    for (index, (file_list, whiteout_paths)) in layers.iter().enumerate() {
+        let layer_file: String = format!("{layout_directory}/layer_{index}.tar");
+
+        match whiteout_paths.len() {
+            0 => {
+                if create_layer_tarball(
+                    input_directory,
+                    &layer_file,
+                    file_list,
+                    &source_date_epoch,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    return Err(());
+                };
+            }
+            _ => {
+                if create_layer_with_whiteouts(
+                    input_directory,
+                    &layer_file,
+                    file_list,
+                    whiteout_paths,
+                    workspace_directory,
+                    &source_date_epoch,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    return Err(());
+                };
+            }
+        };
+
+        match calculate_file_checksum("sha256", &layer_file, &message_config) {
+            Ok(result) => diff_ids.push(format!("\"sha256:{result}\"")),
+            Err(..) => return Err(()),
+        };
+
+        print_message(
+            "debug",
+            &format!("compressing layer: \"{layer_file}\""),
+            &message_config,
+        );
+
+        if run_cmd!(gzip --no-name "$layer_file" 2> /dev/stdout).is_err() == true {
+            print_message(
+                "error",
+                &format!("failed to compress layer: \"{layer_file}\""),
+                &message_config,
+            );
+            return Err(());
+        };
+
+        let layer_digest: String;
+
+        match calculate_file_checksum("sha256", &format!("{layer_file}.gz"), &message_config) {
+            Ok(result) => layer_digest = result,
+            Err(..) => return Err(()),
+        };
+
+        let layer_size: u64;
+
+        match std::fs::metadata(format!("{layer_file}.gz")) {
+            Ok(result) => layer_size = result.size(),
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("failed to read metadata of file: \"{layer_file}.gz\""),
+                    &message_config,
+                );
+                return Err(());
+            }
+        };
+
+        if std::fs::rename(
+            format!("{layer_file}.gz"),
+            format!("{blobs_directory}/{layer_digest}"),
+        )
+        .is_err()
+            == true
+        {
+            print_message(
+                "error",
+                &format!("failed to move file: \"{layer_file}.gz\""),
+                &message_config,
+            );
+            return Err(());
+        };
+
+        layer_descriptors.push(format!(
+            "{{\"mediaType\":\"{LAYER_MEDIA_TYPE}\",\"digest\":\"sha256:{layer_digest}\",\"size\":{layer_size}}}"
+        ));
+    }
+
+    //////////////////////////////////////////////
+
+    let (oci_architecture_name, oci_architecture_variant) = get_oci_architecture_name(architecture);
+
+    let mut platform: String = format!(
+        "\"architecture\":\"{}\",\"os\":\"linux\"",
+        escape_json_string(&oci_architecture_name)
+    );
+
+    if oci_architecture_variant.is_empty() == false {
+        platform.push_str(&format!(
+            ",\"variant\":\"{}\"",
+            escape_json_string(&oci_architecture_variant)
+        ));
+    };
+
+    let config: String = format!(
+        "{{\"created\":\"{created}\",{platform},\"config\":{{\"Env\":[\"PATH=/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin\"],\"Cmd\":[\"bash\"]}},\"rootfs\":{{\"type\":\"layers\",\"diff_ids\":[{}]}},\"history\":[{}]}}",
+        diff_ids.join(","),
+        diff_ids
+            .iter()
+            .map(|_diff_id| format!("{{\"created\":\"{created}\",\"created_by\":\"debstrap\"}}"))
+            .collect::<Vec<String>>()
+            .join(","),
+    );
+
+    let config_digest: String = format!("{:x}", Sha256::digest(config.as_bytes()));
+
+    if create_file(
+        &format!("{blobs_directory}/{config_digest}"),
+        &config,
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    let manifest: String = format!(
+        "{{\"schemaVersion\":2,\"mediaType\":\"{MANIFEST_MEDIA_TYPE}\",\"config\":{{\"mediaType\":\"{CONFIG_MEDIA_TYPE}\",\"digest\":\"sha256:{config_digest}\",\"size\":{}}},\"layers\":[{}]}}",
+        config.len(),
+        layer_descriptors.join(","),
+    );
+
+    let manifest_digest: String = format!("{:x}", Sha256::digest(manifest.as_bytes()));
+
+    if create_file(
+        &format!("{blobs_directory}/{manifest_digest}"),
+        &manifest,
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    let index: String = format!(
+        "{{\"schemaVersion\":2,\"mediaType\":\"application/vnd.oci.image.index.v1+json\",\"manifests\":[{{\"mediaType\":\"{MANIFEST_MEDIA_TYPE}\",\"digest\":\"sha256:{manifest_digest}\",\"size\":{},\"platform\":{{{platform}}},\"annotations\":{{\"org.opencontainers.image.ref.name\":\"{}\"}}}}]}}",
+        manifest.len(),
+        escape_json_string(reference_name),
+    );
+
+    if create_file(
+        &format!("{layout_directory}/index.json"),
+        &index,
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    if create_file(
+        &format!("{layout_directory}/oci-layout"),
+        "{\"imageLayoutVersion\":\"1.0.0\"}",
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    //////////////////////////////////////////////
+
+    if *create_archive == true {
+        if create_layer_tarball(
+            &layout_directory,
+            output_location,
+            &None,
+            &source_date_epoch,
+            &message_config,
+        )
+        .is_err()
+            == true
+        {
+            return Err(());
+        };
+
+        if remove_directory(&layout_directory, &message_config).is_err() == true {
+            return Err(());
+        };
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn get_compressor(compression: &str) -> Result<(String, Vec<String>, String), ()> {
+    let program: &str;
+    let arguments: Vec<&str>;
+    let suffix: &str;
+
+    // the options are chosen so the result can also be unpacked by the Linux kernel (legacy lz4
+    // frames and crc32 checks for xz).
+    match compression {
+        "bzip2" => (program, arguments, suffix) = ("bzip2", Vec::new(), ".bz2"),
+        "gzip" => (program, arguments, suffix) = ("gzip", Vec::from(["--no-name"]), ".gz"),
+        "lz4" => (program, arguments, suffix) = ("lz4", Vec::from(["-l", "-q"]), ".lz4"),
+        "lzma" => (program, arguments, suffix) = ("xz", Vec::from(["--format=lzma"]), ".lzma"),
+        "lzo" => (program, arguments, suffix) = ("lzop", Vec::new(), ".lzo"),
+        "xz" => (program, arguments, suffix) = ("xz", Vec::from(["--check=crc32"]), ".xz"),
+        "zstd" => (program, arguments, suffix) = ("zstd", Vec::from(["-q"]), ".zst"),
+        _ => return Err(()),
+    };
+
+    return Ok((
+        String::from(program),
+        arguments
+            .iter()
+            .map(|argument| String::from(*argument))
+            .collect::<Vec<String>>(),
+        String::from(suffix),
+    ));
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn create_cpio_archive(
+    input_directory: &str,
+    output_file: &str,
+    compression: &str,
+    workspace_directory: &str,
+    source_date_epoch: &Option<u64>,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("creating cpio archive: \"{output_file}\" using compression: \"{compression}\""),
+        &message_config,
+    );
+
+    let (compressor_program, compressor_arguments): (String, Vec<String>) = match compression {
+        "none" => (String::from("cat"), Vec::new()),
+        _ => match get_compressor(compression) {
+            Ok((program, arguments, _suffix)) => (program, arguments),
+            Err(..) => {
+                print_message(
+                    "error",
+                    &format!("unrecognized compression: \"{compression}\""),
+                    &message_config,
+                );
+                return Err(());
+            }
+        },
+    };
+
+    // entries are sorted by their path components like "tar --sort=name" does (so "a/x" comes
+    // before "a-b"), cpio always stores numeric ownership and '--reproducible' renumbers the inodes.
+    let directory_tree: HashMap<String, String>;
+
+    match list_directory_tree(input_directory, &message_config) {
+        Ok(result) => directory_tree = result,
+        Err(..) => return Err(()),
+    };
+
+    let mut file_list: Vec<&String> = directory_tree.keys().collect::<Vec<&String>>();
+
+    file_list.sort_unstable_by(|a, b| a.split('/').cmp(b.split('/')));
+
+    // cpio has no equivalent of tar's '--clamp-mtime' so the timestamps of the (temporary) input
+    // directory are clamped in place, the modification time is the sixth part of the signature.
+    match source_date_epoch {
+        Some(epoch) => {
+            print_message(
+                "debug",
+                &format!("clamping timestamps in: \"{input_directory}\" to: \"{epoch}\""),
+                &message_config,
+            );
+
+            let newer_paths: Vec<String> = file_list
+                .iter()
+                .filter(|path| {
+                    let (seconds, nanoseconds) = directory_tree[path.as_str()]
+                        .split_whitespace()
+                        .nth(5)
+                        .unwrap_or_default()
+                        .split_once('.')
+                        .unwrap_or_default();
+
+                    let seconds: i64 = seconds.parse::<i64>().unwrap_or(0);
+
+                    return seconds > *epoch as i64
+                        || (seconds == *epoch as i64 && nanoseconds != "0");
+                })
+                .map(|path| format!("{input_directory}/{path}"))
+                .collect::<Vec<String>>();
+
+            let date: String = format!("@{epoch}");
+
+            for paths in newer_paths.chunks(1024) {
+                if run_cmd!(touch --no-dereference --date=$date -- $[paths] 2> /dev/stdout).is_err()
+                    == true
+                {
+                    print_message(
+                        "error",
+                        &format!("failed to clamp timestamps in: \"{input_directory}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                };
+            }
+        }
+        None => {}
+    };
+
+    // the list is kept in the workspace so nothing is left next to the output if cpio fails.
+    let file_list_location: String = format!("{workspace_directory}/cpio_file_list");
+
+    if create_file(
+        &file_list_location,
+        &file_list
+            .iter()
+            .map(|file| format!("{file}\0"))
+            .collect::<String>(),
+        None,
+        None,
+        &message_config,
+    )
+    .is_err()
+        == true
+    {
+        return Err(());
+    };
+
+    if run_cmd!(
+        cd $input_directory;
+        cpio --create --null --format=newc --reproducible --quiet < $file_list_location | $compressor_program $[compressor_arguments] > $output_file 2> /dev/stdout
+    )
+    .is_err()
+        == true
+    {
+        print_message(
+            "error",
+            &format!("failed to create cpio archive: \"{output_file}\""),
+            &message_config,
+        );
+        std::fs::remove_file(&file_list_location).unwrap_or(());
+        return Err(());
+    };
+
+    if std::fs::remove_file(&file_list_location).is_err() == true {
+        print_message(
+            "error",
+            &format!("failed to remove file: \"{file_list_location}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    return Ok(());
+}
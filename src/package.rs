@@ -1,14 +1,20 @@
+use crate::deb822::*;
 use crate::functions::*;
 
 use byte_unit::{Byte, Unit, UnitType};
 use cmd_lib::{run_cmd, run_fun};
+use std::cmp::Ordering;
+use std::collections::HashSet;
 use std::path::Path;
+use std::rc::Rc;
 
 #[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Relationship {
-    pub name: String,
+    pub name: Rc<str>,
     pub version: String,
     pub architecture: String,
+    pub architecture_restrictions: Vec<String>,
+    pub profile_restrictions: Vec<Vec<String>>,
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -16,8 +22,11 @@ pub struct Package {
     pub name: String,
     pub version: String,
     pub architecture: String,
-    pub section: String,
+    pub source: String,
+    pub source_version: String,
+    pub section: Rc<str>,
     pub priority: String,
+    pub multi_arch: String,
     pub depends: Vec<Vec<Relationship>>,
     pub pre_depends: Vec<Vec<Relationship>>,
     pub recommends: Vec<Vec<Relationship>>,
@@ -34,17 +43,82 @@ pub struct Package {
     pub installed_size: u64,
     pub sha256_hash: String,
     pub md5_hash: String,
-    pub maintainer: String,
+    pub maintainer: Rc<str>,
     pub description: String,
     pub homepage: String,
+    pub other_fields: Vec<(Rc<str>, String)>,
     pub origin_suite: String,
     pub origin_component: String,
     pub origin_architecture: String,
     pub origin_uri_scheme: String,
     pub origin_uri_path: String,
+    pub pin_priority: i32,
+}
+
+const KNOWN_PACKAGE_FIELDS: [&str; 26] = [
+    "Package",
+    "Version",
+    "Architecture",
+    "Source",
+    "Section",
+    "Priority",
+    "Multi-Arch",
+    "Depends",
+    "Pre-Depends",
+    "Recommends",
+    "Suggests",
+    "Enhances",
+    "Breaks",
+    "Conflicts",
+    "Provides",
+    "Replaces",
+    "Essential",
+    "Build-Essential",
+    "Filename",
+    "Size",
+    "Installed-Size",
+    "SHA256",
+    "MD5sum",
+    "Maintainer",
+    "Description",
+    "Homepage",
+];
+
+// strings that repeat across many packages, like maintainers, sections, and the names in
+// relationships, share one allocation.
+#[derive(Debug, Default)]
+pub struct StringInterner {
+    strings: HashSet<Rc<str>>,
+}
+
+impl StringInterner {
+    pub fn intern(&mut self, input: &str) -> Rc<str> {
+        match self.strings.get(input) {
+            Some(result) => return result.clone(),
+            None => {
+                let interned: Rc<str> = Rc::from(input);
+
+                self.strings.insert(interned.clone());
+
+                return interned;
+            }
+        };
+    }
+
+    pub fn intern_relationships(&mut self, relationships: &mut Vec<Vec<Relationship>>) {
+        for relationship in relationships.iter_mut().flatten() {
+            relationship.name = self.intern(&relationship.name);
+        }
+    }
 }
 
-fn parse_relationships(prefix: &str, input: &str) -> Vec<Vec<Relationship>> {
+// packages of the primary architecture and of architecture 'all' are native, the others are foreign.
+pub struct ArchitectureConfig {
+    pub primary_architecture: String,
+    pub list_of_architectures: Vec<String>,
+}
+
+pub fn parse_relationships(prefix: &str, input: &str) -> Vec<Vec<Relationship>> {
     let mut output: Vec<Vec<Relationship>> = Vec::new();
 
     for entry in input
@@ -53,6 +127,10 @@ fn parse_relationships(prefix: &str, input: &str) -> Vec<Vec<Relationship>> {
         .map(|element| String::from(element.trim()))
         .collect::<Vec<String>>()
     {
+        if entry.is_empty() == true {
+            continue;
+        };
+
         let mut relationship_enties: Vec<Relationship> = Vec::new();
 
         for alternative in entry
@@ -61,28 +139,49 @@ fn parse_relationships(prefix: &str, input: &str) -> Vec<Vec<Relationship>> {
             .collect::<Vec<String>>()
         {
             let mut relationship: Relationship = Default::default();
+            let mut name: String = String::new();
+
+            // e.g. "libfoo-dev:native (>= 1.2) [linux-any !hurd-i386] <!nocheck> <stage1>"
+            let mut current_delimiter: char = ' ';
+            let mut current_value: String = String::new();
+
+            for character in alternative.chars() {
+                match (current_delimiter, character) {
+                    (' ', '(') | (' ', '[') | (' ', '<') => {
+                        current_delimiter = character;
+                        current_value = String::new();
+                    }
+                    ('(', ')') => {
+                        relationship.version = String::from(current_value.trim());
+                        current_delimiter = ' ';
+                    }
+                    ('[', ']') => {
+                        relationship.architecture_restrictions = current_value
+                            .split_whitespace()
+                            .map(|element| String::from(element))
+                            .collect::<Vec<String>>();
+                        current_delimiter = ' ';
+                    }
+                    ('<', '>') => {
+                        relationship.profile_restrictions.push(
+                            current_value
+                                .split_whitespace()
+                                .map(|element| String::from(element))
+                                .collect::<Vec<String>>(),
+                        );
+                        current_delimiter = ' ';
+                    }
+                    (' ', _) => name.push(character),
+                    _ => current_value.push(character),
+                };
+            }
 
-            let split_line: Vec<String> = alternative
-                .replacen(")", "", 1)
-                .split(" (")
-                .map(|element| String::from(element.trim()))
-                .collect::<Vec<String>>();
-
-            if split_line[0].contains(":") == true {
-                let split_string: Vec<String> = split_line[0]
-                    .clone()
-                    .split(':')
-                    .map(|element| String::from(element))
-                    .collect::<Vec<String>>();
-
-                relationship.name = split_string[0].clone();
-                relationship.architecture = split_string[1].clone();
-            } else {
-                relationship.name = split_line[0].clone();
-            };
-
-            if split_line.len() == 2 {
-                relationship.version = split_line[1].clone();
+            match name.trim().split_once(':') {
+                Some((package_name, architecture)) => {
+                    relationship.name = Rc::from(package_name);
+                    relationship.architecture = String::from(architecture);
+                }
+                None => relationship.name = Rc::from(name.trim()),
             };
 
             relationship_enties.push(relationship);
@@ -94,150 +193,388 @@ fn parse_relationships(prefix: &str, input: &str) -> Vec<Vec<Relationship>> {
     return output;
 }
 
+pub fn does_relationship_apply(
+    relationship: &Relationship,
+    architecture: &str,
+    build_profiles: &Vec<String>,
+) -> bool {
+    // an architecture list either only contains negated or only non-negated entries.
+    if relationship.architecture_restrictions.len() != 0 {
+        let is_negated: bool = relationship.architecture_restrictions[0].starts_with("!");
+
+        let does_any_match: bool = relationship
+            .architecture_restrictions
+            .iter()
+            .any(|wildcard| {
+                does_architecture_match_wildcard(architecture, wildcard.trim_start_matches('!'))
+            });
+
+        if does_any_match == is_negated {
+            return false;
+        };
+    };
+
+    // the terms inside of '<...>' are AND-ed, while the groups themselves are OR-ed.
+    if relationship.profile_restrictions.len() != 0 {
+        let is_any_group_satisfied: bool = relationship.profile_restrictions.iter().any(|group| {
+            group.iter().all(|term| match term.strip_prefix('!') {
+                Some(profile) => build_profiles.contains(&String::from(profile)) == false,
+                None => build_profiles.contains(term) == true,
+            })
+        });
+
+        if is_any_group_satisfied == false {
+            return false;
+        };
+    };
+
+    return true;
+}
+
+pub fn apply_restrictions(
+    relationships: &Vec<Vec<Relationship>>,
+    architecture: &str,
+    build_profiles: &Vec<String>,
+) -> Vec<Vec<Relationship>> {
+    let mut output: Vec<Vec<Relationship>> = Vec::new();
+
+    // alternatives that don't apply are dropped, and so is a relation without any alternatives.
+    for relationship in relationships {
+        let alternatives: Vec<Relationship> = relationship
+            .iter()
+            .filter(|alternative| {
+                does_relationship_apply(alternative, architecture, build_profiles) == true
+            })
+            .map(|alternative| Relationship {
+                architecture_restrictions: Vec::new(),
+                profile_restrictions: Vec::new(),
+                ..alternative.clone()
+            })
+            .collect::<Vec<Relationship>>();
+
+        if alternatives.len() != 0 {
+            output.push(alternatives);
+        };
+    }
+
+    return output;
+}
+
+pub fn apply_package_list_restrictions(
+    package_list: &Vec<String>,
+    architecture: &str,
+    build_profiles: &Vec<String>,
+) -> Vec<String> {
+    let mut output: Vec<String> = Vec::new();
+
+    // e.g. "grub-pc [amd64 i386]" is only kept for those architectures.
+    for entry in package_list {
+        for relationship in parse_relationships("", entry).iter().flatten() {
+            if does_relationship_apply(relationship, architecture, build_profiles) == false {
+                continue;
+            };
+
+            match relationship.architecture.is_empty() {
+                true => output.push(String::from(&*relationship.name)),
+                false => output.push(format!(
+                    "{}:{}",
+                    relationship.name, relationship.architecture
+                )),
+            };
+        }
+    }
+
+    return output;
+}
+
 impl Package {
     pub fn new(
-        package_entries: &str,
+        paragraph: &Paragraph,
         origin_suite: &str,
         origin_component: &str,
         origin_architecture: &str,
         origin_uri_scheme: &str,
         origin_uri_path: &str,
-    ) -> Self {
-        let mut name: String = String::new();
-        let mut version: String = String::new();
-        let mut architecture: String = String::new();
-        let mut section: String = String::new();
-        let mut priority: String = String::new();
-        let mut depends: Vec<Vec<Relationship>> = Vec::new();
-        let mut pre_depends: Vec<Vec<Relationship>> = Vec::new();
-        let mut recommends: Vec<Vec<Relationship>> = Vec::new();
-        let mut suggests: Vec<Vec<Relationship>> = Vec::new();
-        let mut enhances: Vec<Vec<Relationship>> = Vec::new();
-        let mut breaks: Vec<Vec<Relationship>> = Vec::new();
-        let mut conflicts: Vec<Vec<Relationship>> = Vec::new();
-        let mut provides: Vec<Vec<Relationship>> = Vec::new();
-        let mut replaces: Vec<Vec<Relationship>> = Vec::new();
-        let mut is_essential: bool = false;
-        let mut is_build_essential: bool = false;
-        let mut file_name: String = String::new();
-        let mut file_size: u64 = 0;
-        let mut installed_size: u64 = 0;
-        let mut sha256_hash: String = String::new();
-        let mut md5_hash: String = String::new();
-        let mut maintainer: String = String::new();
-        let mut description: String = String::new();
-        let mut homepage: String = String::new();
-
-        for line in package_entries.lines() {
-            match &line as &str {
-                _ if line.starts_with("Package: ") => {
-                    name = line.replacen("Package: ", "", 1);
-                }
-                _ if line.starts_with("Version: ") => {
-                    version = line.replacen("Version: ", "", 1);
-                }
-                _ if line.starts_with("Architecture: ") => {
-                    architecture = line.replacen("Architecture: ", "", 1);
-                }
-                _ if line.starts_with("Section: ") => {
-                    section = line.replacen("Section: ", "", 1);
-                }
-                _ if line.starts_with("Priority: ") => {
-                    priority = line.replacen("Priority: ", "", 1);
-                }
-                _ if line.starts_with("Depends: ") => {
-                    depends = parse_relationships("Depends: ", line);
-                }
-                _ if line.starts_with("Pre-Depends: ") => {
-                    pre_depends = parse_relationships("Pre-Depends: ", line);
-                }
-                _ if line.starts_with("Recommends: ") => {
-                    recommends = parse_relationships("Recommends: ", line);
-                }
-                _ if line.starts_with("Suggests: ") => {
-                    suggests = parse_relationships("Suggests: ", line);
-                }
-                _ if line.starts_with("Enhances: ") => {
-                    enhances = parse_relationships("Enhances: ", line);
-                }
-                _ if line.starts_with("Breaks: ") => {
-                    breaks = parse_relationships("Breaks: ", line);
-                }
-                _ if line.starts_with("Conflicts: ") => {
-                    conflicts = parse_relationships("Conflicts: ", line);
-                }
-                _ if line.starts_with("Provides: ") => {
-                    provides = parse_relationships("Provides: ", line);
-                }
-                _ if line.starts_with("Replaces: ") => {
-                    replaces = parse_relationships("Replaces: ", line);
-                }
-                "Essential: yes" => {
-                    is_essential = true;
-                }
-                "Build-Essential: yes" => {
-                    is_build_essential = true;
-                }
-                _ if line.starts_with("Filename: ") => {
-                    file_name = line.replacen("Filename: ", "", 1);
-                }
-                _ if line.starts_with("Size: ") => {
-                    file_size = line.replacen("Size: ", "", 1).parse().unwrap();
-                }
-                _ if line.starts_with("Installed-Size: ") => {
-                    installed_size = line.replacen("Installed-Size: ", "", 1).parse().unwrap();
-                }
-                _ if line.starts_with("SHA256: ") => {
-                    sha256_hash = line.replacen("SHA256: ", "", 1);
-                }
-                _ if line.starts_with("MD5sum: ") => {
-                    md5_hash = line.replacen("MD5sum: ", "", 1);
-                }
-                _ if line.starts_with("Maintainer: ") => {
-                    maintainer = line.replacen("Maintainer: ", "", 1);
+        string_interner: &mut StringInterner,
+    ) -> Result<Self, Deb822Error> {
+        let mut required_values: Vec<String> = Vec::new();
+
+        for field in ["Package", "Version", "Architecture"] {
+            match paragraph.get(field) {
+                Some(value) if value.is_empty() == false => {
+                    required_values.push(String::from(value));
                 }
-                _ if line.starts_with("Description: ") => {
-                    description = line.replacen("Description: ", "", 1).replace("—", "-");
+                _ => {
+                    return Err(paragraph.error(field, &format!("missing field: \"{field}\"")));
                 }
-                _ if line.starts_with("Homepage: ") => {
-                    homepage = line.replacen("Homepage: ", "", 1);
+            };
+        }
+
+        let name: String = required_values[0].clone();
+        let version: String = required_values[1].clone();
+        let architecture: String = required_values[2].clone();
+
+        //////////////////////////////////////
+
+        let mut source: String = String::new();
+        let mut source_version: String = String::new();
+
+        // the source package's version is only listed when it differs from the binary package's
+        // version, e.g. "Source: glibc (2.41-6)".
+        match paragraph.get("Source") {
+            Some(value) => match value.split_once(" (") {
+                Some((source_name, version_of_source)) => {
+                    source = String::from(source_name.trim());
+                    source_version = String::from(version_of_source.replace(")", "").trim());
                 }
-                _ => {}
+                None => source = String::from(value),
+            },
+            None => {}
+        };
+
+        let mut sizes: Vec<u64> = Vec::new();
+
+        for field in ["Size", "Installed-Size"] {
+            match paragraph.get(field) {
+                Some(value) => match value.parse::<u64>() {
+                    Ok(result) => sizes.push(result),
+                    Err(..) => {
+                        return Err(paragraph.error(
+                            field,
+                            &format!("invalid value of field: \"{field}\": \"{value}\""),
+                        ));
+                    }
+                },
+                None => sizes.push(0),
             };
         }
 
-        Self {
+        let mut relationships: Vec<Vec<Vec<Relationship>>> = Vec::new();
+
+        for field in [
+            "Depends",
+            "Pre-Depends",
+            "Recommends",
+            "Suggests",
+            "Enhances",
+            "Breaks",
+            "Conflicts",
+            "Provides",
+            "Replaces",
+        ] {
+            let mut parsed: Vec<Vec<Relationship>> =
+                parse_relationships("", &paragraph.get_folded(field).unwrap_or_default());
+
+            string_interner.intern_relationships(&mut parsed);
+
+            relationships.push(parsed);
+        }
+
+        // fields that aren't parsed above are kept as they are, e.g. "Tag" or
+        // "Phased-Update-Percentage".
+        let mut other_fields: Vec<(Rc<str>, String)> = Vec::new();
+
+        for field in &paragraph.fields {
+            if KNOWN_PACKAGE_FIELDS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(&field.name) == true)
+                == false
+            {
+                other_fields.push((string_interner.intern(&field.name), field.value.clone()));
+            };
+        }
+
+        let get_value = |field: &str| -> String {
+            return String::from(paragraph.get(field).unwrap_or_default());
+        };
+
+        //////////////////////////////////////
+
+        let mut relationships: std::vec::IntoIter<Vec<Vec<Relationship>>> =
+            relationships.into_iter();
+
+        let mut package: Package = Package {
             name: name,
             version: version,
             architecture: architecture,
-            section: section,
-            priority: priority,
-            depends: depends,
-            pre_depends: pre_depends,
-            recommends: recommends,
-            suggests: suggests,
-            enhances: enhances,
-            breaks: breaks,
-            conflicts: conflicts,
-            provides: provides,
-            replaces: replaces,
-            is_essential: is_essential,
-            is_build_essential: is_build_essential,
-            file_name: file_name,
-            file_size: file_size,
-            installed_size: installed_size,
-            sha256_hash: sha256_hash,
-            md5_hash: md5_hash,
-            maintainer: maintainer,
-            description: description,
-            homepage: homepage,
+            source: source,
+            source_version: source_version,
+            section: string_interner.intern(&get_value("Section")),
+            priority: get_value("Priority"),
+            multi_arch: get_value("Multi-Arch"),
+            depends: relationships.next().unwrap_or_default(),
+            pre_depends: relationships.next().unwrap_or_default(),
+            recommends: relationships.next().unwrap_or_default(),
+            suggests: relationships.next().unwrap_or_default(),
+            enhances: relationships.next().unwrap_or_default(),
+            breaks: relationships.next().unwrap_or_default(),
+            conflicts: relationships.next().unwrap_or_default(),
+            provides: relationships.next().unwrap_or_default(),
+            replaces: relationships.next().unwrap_or_default(),
+            is_essential: get_value("Essential") == "yes",
+            is_build_essential: get_value("Build-Essential") == "yes",
+            file_name: get_value("Filename"),
+            file_size: sizes[0],
+            installed_size: sizes[1],
+            sha256_hash: get_value("SHA256"),
+            md5_hash: get_value("MD5sum"),
+            maintainer: string_interner.intern(&get_value("Maintainer")),
+            // only the synopsis, the first line of the description.
+            description: get_value("Description")
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .replace("—", "-"),
+            homepage: get_value("Homepage"),
+            other_fields: other_fields,
             origin_suite: String::from(origin_suite),
             origin_component: String::from(origin_component),
             origin_architecture: String::from(origin_architecture),
             origin_uri_scheme: String::from(origin_uri_scheme),
             origin_uri_path: String::from(origin_uri_path),
+            pin_priority: 500,
+        };
+
+        if package.source.is_empty() == true {
+            package.source = package.name.clone();
+        };
+
+        if package.source_version.is_empty() == true {
+            package.source_version = package.version.clone();
+        };
+
+        return Ok(package);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn compare_version_part(a: &str, b: &str) -> Ordering {
+    // the same algorithm as dpkg's 'verrevcmp', letters sort before non-letters and '~' sorts
+    // before everything, even the end of the string.
+    fn order(character: Option<&u8>) -> i32 {
+        match character {
+            None => return 0,
+            Some(character) if character.is_ascii_digit() == true => return 0,
+            Some(character) if character.is_ascii_alphabetic() == true => {
+                return *character as i32;
+            }
+            Some(b'~') => return -1,
+            Some(character) => return *character as i32 + 256,
+        };
+    }
+
+    let a: &[u8] = a.as_bytes();
+    let b: &[u8] = b.as_bytes();
+
+    let mut a_index: usize = 0;
+    let mut b_index: usize = 0;
+
+    while a_index < a.len() || b_index < b.len() {
+        while (a_index < a.len() && a[a_index].is_ascii_digit() == false)
+            || (b_index < b.len() && b[b_index].is_ascii_digit() == false)
+        {
+            let a_order: i32 = order(a.get(a_index));
+            let b_order: i32 = order(b.get(b_index));
+
+            if a_order != b_order {
+                return a_order.cmp(&b_order);
+            };
+
+            a_index += 1;
+            b_index += 1;
+        }
+
+        while a_index < a.len() && a[a_index] == b'0' {
+            a_index += 1;
+        }
+
+        while b_index < b.len() && b[b_index] == b'0' {
+            b_index += 1;
+        }
+
+        let mut first_difference: Ordering = Ordering::Equal;
+
+        while a_index < a.len()
+            && a[a_index].is_ascii_digit() == true
+            && b_index < b.len()
+            && b[b_index].is_ascii_digit() == true
+        {
+            if first_difference == Ordering::Equal {
+                first_difference = a[a_index].cmp(&b[b_index]);
+            };
+
+            a_index += 1;
+            b_index += 1;
         }
+
+        if a_index < a.len() && a[a_index].is_ascii_digit() == true {
+            return Ordering::Greater;
+        };
+
+        if b_index < b.len() && b[b_index].is_ascii_digit() == true {
+            return Ordering::Less;
+        };
+
+        if first_difference != Ordering::Equal {
+            return first_difference;
+        };
     }
+
+    return Ordering::Equal;
+}
+
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split_version(version: &str) -> (u64, &str, &str) {
+        let (epoch, remaining) = match version.split_once(':') {
+            Some((epoch, remaining)) => (epoch.parse().unwrap_or(0), remaining),
+            None => (0, version),
+        };
+
+        // the revision is everything after the last hyphen, the upstream version may contain
+        // hyphens itself.
+        let (upstream, revision) = match remaining.rsplit_once('-') {
+            Some((upstream, revision)) => (upstream, revision),
+            None => (remaining, ""),
+        };
+
+        return (epoch, upstream, revision);
+    }
+
+    let (a_epoch, a_upstream, a_revision) = split_version(a.trim());
+    let (b_epoch, b_upstream, b_revision) = split_version(b.trim());
+
+    return a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_version_part(a_upstream, b_upstream))
+        .then_with(|| compare_version_part(a_revision, b_revision));
+}
+
+pub fn does_version_satisfy(version: &str, constraint: &str) -> bool {
+    let constraint: &str = constraint.trim();
+
+    if constraint.is_empty() == true {
+        return true;
+    };
+
+    let operator: String = constraint
+        .chars()
+        .take_while(|character| ['<', '>', '='].contains(character) == true)
+        .collect::<String>();
+
+    let required_version: &str = constraint[operator.len()..].trim();
+
+    let ordering: Ordering = compare_versions(version, required_version);
+
+    // '<' and '>' are the deprecated spellings of '<=' and '>='.
+    match &operator as &str {
+        "<<" => return ordering == Ordering::Less,
+        "<=" | "<" => return ordering != Ordering::Greater,
+        "=" | "" => return ordering == Ordering::Equal,
+        ">=" | ">" => return ordering != Ordering::Less,
+        ">>" => return ordering == Ordering::Greater,
+        _ => return false,
+    };
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -269,7 +606,7 @@ fn pretty_print_relationships(
 
             for (index, alternative) in value.iter().enumerate() {
                 if index == 0 {
-                    line_to_print = alternative.name.clone();
+                    line_to_print = String::from(&*alternative.name);
                 } else {
                     line_to_print = format!("{line_to_print} | {}", alternative.name.clone());
                 };
@@ -290,12 +627,49 @@ fn pretty_print_relationships(
     };
 }
 
+pub fn format_relationship_alternatives(alternatives: &Vec<Relationship>) -> String {
+    let mut output: Vec<String> = Vec::new();
+
+    for alternative in alternatives {
+        let mut entry: String = String::from(&*alternative.name);
+
+        if alternative.architecture.is_empty() == false {
+            entry = format!("{entry}:{}", alternative.architecture);
+        };
+
+        if alternative.version.is_empty() == false {
+            entry = format!("{entry} ({})", alternative.version);
+        };
+
+        if alternative.architecture_restrictions.len() != 0 {
+            entry = format!(
+                "{entry} [{}]",
+                alternative.architecture_restrictions.join(" ")
+            );
+        };
+
+        for group in &alternative.profile_restrictions {
+            entry = format!("{entry} <{}>", group.join(" "));
+        }
+
+        output.push(entry);
+    }
+
+    return output.join(" | ");
+}
+
 pub fn pretty_print_package(package: &Package, message_config: &MessageConfig) {
     pretty_print_string("Package", &package.name, &message_config);
     pretty_print_string("Version", &package.version, &message_config);
     pretty_print_string("Architecture", &package.architecture, &message_config);
+    pretty_print_string(
+        "Source",
+        &format!("{} ({})", package.source, package.source_version),
+        &message_config,
+    );
     pretty_print_string("Section", &package.section, &message_config);
     pretty_print_string("Priority", &package.priority, &message_config);
+    pretty_print_string("Multi-Arch", &package.multi_arch, &message_config);
     pretty_print_relationships("Depends", &package.depends, &message_config);
     pretty_print_relationships("Pre-Depends", &package.pre_depends, &message_config);
     pretty_print_relationships("Recommends", &package.recommends, &message_config);
@@ -355,6 +729,27 @@ pub fn pretty_print_package(package: &Package, message_config: &MessageConfig) {
         ),
         &message_config,
     );
+    pretty_print_string(
+        "Pin Priority",
+        &package.pin_priority.to_string(),
+        &message_config,
+    );
+}
+
+pub fn pretty_print_files(files: &Vec<String>, message_config: &MessageConfig) {
+    if files.len() != 0 {
+        if message_config.color == true {
+            println!("\x1b[01mFiles\x1b[00m: [");
+        } else {
+            println!("Files: [");
+        };
+
+        for file in files {
+            println!("    /{file}");
+        }
+
+        println!("]");
+    };
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -876,6 +1271,103 @@ pub fn extract_deb_control_field(
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
+// extracts the control archive, i.e. the 'control' file and the maintainer scripts.
+pub fn extract_deb_control(
+    extractor: &str,
+    package: &str,
+    output_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    match extractor as &str {
+        "ar" => {
+            let tarball: String;
+
+            match run_fun!(ar -t "$package" | grep --regexp="^control.tar") {
+                Ok(result) => {
+                    tarball = result;
+                }
+                Err(..) => {
+                    print_message(
+                        "error",
+                        &format!("failed to read file: \"{package}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                }
+            };
+
+            let is_extraction_successful: bool;
+
+            match &tarball as &str {
+                "control.tar" => {
+                    is_extraction_successful = run_cmd!(
+                        ar -p "$package" "$tarball" |
+                            tar --extract --directory="$output_directory"
+                    )
+                    .is_ok();
+                }
+                "control.tar.bz2" => {
+                    is_extraction_successful = run_cmd!(
+                        ar -p "$package" "$tarball" |
+                            bzip2 --decompress --stdout --force |
+                            tar --extract --directory="$output_directory"
+                    )
+                    .is_ok();
+                }
+                "control.tar.gz" => {
+                    is_extraction_successful = run_cmd!(
+                        ar -p "$package" "$tarball" |
+                            gzip --decompress --to-stdout --force |
+                            tar --extract --directory="$output_directory"
+                    )
+                    .is_ok();
+                }
+                "control.tar.xz" => {
+                    is_extraction_successful = run_cmd!(
+                        ar -p "$package" "$tarball" |
+                            xz --decompress --to-stdout --force |
+                            tar --extract --directory="$output_directory"
+                    )
+                    .is_ok();
+                }
+                "control.tar.zst" => {
+                    is_extraction_successful = run_cmd!(
+                        ar -p "$package" "$tarball" |
+                            zstd --decompress --stdout --force |
+                            tar --extract --directory="$output_directory"
+                    )
+                    .is_ok();
+                }
+                _ => is_extraction_successful = false,
+            };
+
+            if is_extraction_successful == false {
+                print_message(
+                    "error",
+                    &format!("failed to extract control archive of package: \"{package}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+        }
+        "dpkg-deb" => {
+            if run_cmd!(dpkg-deb --control "$package" "$output_directory").is_err() == true {
+                print_message(
+                    "error",
+                    &format!("failed to extract control archive of package: \"{package}\""),
+                    &message_config,
+                );
+                return Err(());
+            };
+        }
+        _ => {}
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
 pub fn extract_deb_data(
     extractor: &str,
     package: &str,
@@ -1003,3 +1495,210 @@ pub fn extract_deb_data(
 
     return Ok(());
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the vectors are taken from dpkg's own version tests and the Debian Policy.
+    #[test]
+    fn compare_versions_like_dpkg() {
+        let vectors: Vec<(&str, &str, Ordering)> = Vec::from([
+            ("1.0", "1.0", Ordering::Equal),
+            ("1.0", "1.0-0", Ordering::Equal),
+            ("0:1.0", "1.0", Ordering::Equal),
+            ("1.001", "1.1", Ordering::Equal),
+            ("1.0", "1.1", Ordering::Less),
+            ("1.2.3", "1.2.10", Ordering::Less),
+            ("1:0.1", "2.0", Ordering::Greater),
+            ("1:1.0", "2:0.1", Ordering::Less),
+            ("1.0-1", "1.0-2", Ordering::Less),
+            ("1.0-1ubuntu1", "1.0-1", Ordering::Greater),
+            ("1.0-1~bpo1", "1.0-1", Ordering::Less),
+            ("2.41-6", "2.41-6+b1", Ordering::Less),
+            ("1.0-a-1", "1.0-b-1", Ordering::Less),
+            ("1.0a", "1.0+", Ordering::Less),
+            ("1.0~rc1", "1.0", Ordering::Less),
+            ("1.0~~", "1.0~~a", Ordering::Less),
+            ("1.0~~a", "1.0~", Ordering::Less),
+            ("1.0~", "1.0", Ordering::Less),
+            ("1.0", "1.0a", Ordering::Less),
+        ]);
+
+        for (a, b, ordering) in vectors {
+            assert_eq!(compare_versions(a, b), ordering, "{a} <=> {b}");
+            assert_eq!(compare_versions(b, a), ordering.reverse(), "{b} <=> {a}");
+        }
+    }
+
+    #[test]
+    fn check_version_constraints() {
+        assert_eq!(does_version_satisfy("1.0-1", ">= 1.0"), true);
+        assert_eq!(does_version_satisfy("1.0~rc1", ">= 1.0"), false);
+        assert_eq!(does_version_satisfy("1.0", "<< 1.0"), false);
+        assert_eq!(does_version_satisfy("1.0", "<= 1.0"), true);
+        assert_eq!(does_version_satisfy("1:0.1", ">> 2.0"), true);
+        assert_eq!(does_version_satisfy("1.0", "= 0:1.0"), true);
+        assert_eq!(does_version_satisfy("1.0", ""), true);
+    }
+
+    #[test]
+    fn parse_relationship_fields() {
+        let vectors: Vec<(&str, &str, &str, &str, Vec<&str>, Vec<Vec<&str>>)> = Vec::from([
+            ("foo", "foo", "", "", Vec::new(), Vec::new()),
+            ("foo:any", "foo", "any", "", Vec::new(), Vec::new()),
+            ("foo (>= 1.0)", "foo", "", ">= 1.0", Vec::new(), Vec::new()),
+            (
+                "foo [amd64 arm64]",
+                "foo",
+                "",
+                "",
+                Vec::from(["amd64", "arm64"]),
+                Vec::new(),
+            ),
+            (
+                "foo [!hurd-i386]",
+                "foo",
+                "",
+                "",
+                Vec::from(["!hurd-i386"]),
+                Vec::new(),
+            ),
+            (
+                "foo:any (>= 1.0) [amd64] <!nocheck> <stage1>",
+                "foo",
+                "any",
+                ">= 1.0",
+                Vec::from(["amd64"]),
+                Vec::from([Vec::from(["!nocheck"]), Vec::from(["stage1"])]),
+            ),
+            (
+                "foo <!nocheck !nodoc>",
+                "foo",
+                "",
+                "",
+                Vec::new(),
+                Vec::from([Vec::from(["!nocheck", "!nodoc"])]),
+            ),
+        ]);
+
+        for (input, name, architecture, version, architectures, profiles) in vectors {
+            let output: Vec<Vec<Relationship>> =
+                parse_relationships("Depends: ", &format!("Depends: {input}"));
+
+            assert_eq!(output.len(), 1, "{input}");
+            assert_eq!(output[0].len(), 1, "{input}");
+
+            let relationship: &Relationship = &output[0][0];
+
+            assert_eq!(&relationship.name as &str, name, "{input}");
+            assert_eq!(relationship.architecture, architecture, "{input}");
+            assert_eq!(relationship.version, version, "{input}");
+            assert_eq!(
+                relationship.architecture_restrictions, architectures,
+                "{input}"
+            );
+            assert_eq!(relationship.profile_restrictions, profiles, "{input}");
+        }
+
+        let output: Vec<Vec<Relationship>> =
+            parse_relationships("Depends: ", "Depends: foo | bar (<< 2), baz,");
+
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[0].len(), 2);
+        assert_eq!(&output[0][1].name as &str, "bar");
+        assert_eq!(output[0][1].version, "<< 2");
+        assert_eq!(&output[1][0].name as &str, "baz");
+    }
+
+    #[test]
+    fn check_relationship_restrictions() {
+        let vectors: Vec<(&str, &str, Vec<&str>, bool)> = Vec::from([
+            ("foo [amd64]", "amd64", Vec::new(), true),
+            ("foo [amd64]", "arm64", Vec::new(), false),
+            ("foo [!amd64]", "amd64", Vec::new(), false),
+            ("foo [!amd64]", "arm64", Vec::new(), true),
+            ("foo [!amd64 !arm64]", "armhf", Vec::new(), true),
+            ("foo [!amd64 !arm64]", "arm64", Vec::new(), false),
+            ("foo [linux-any]", "armhf", Vec::new(), true),
+            ("foo [!linux-any]", "arm64", Vec::new(), false),
+            ("foo [any-arm]", "armhf", Vec::new(), true),
+            ("foo [any-arm]", "arm64", Vec::new(), false),
+            ("foo <!nocheck>", "amd64", Vec::new(), true),
+            ("foo <!nocheck>", "amd64", Vec::from(["nocheck"]), false),
+            ("foo <stage1>", "amd64", Vec::new(), false),
+            (
+                "foo <!nocheck> <stage1>",
+                "amd64",
+                Vec::from(["nocheck", "stage1"]),
+                true,
+            ),
+            (
+                "foo <!nocheck stage1>",
+                "amd64",
+                Vec::from(["nocheck", "stage1"]),
+                false,
+            ),
+            (
+                "foo:any (>= 1.0) [amd64] <!nocheck> <stage1>",
+                "amd64",
+                Vec::new(),
+                true,
+            ),
+            (
+                "foo:any (>= 1.0) [amd64] <!nocheck> <stage1>",
+                "arm64",
+                Vec::new(),
+                false,
+            ),
+        ]);
+
+        for (input, architecture, build_profiles, result) in vectors {
+            let relationship: Relationship = parse_relationships("", input)[0][0].clone();
+            let build_profiles: Vec<String> = build_profiles
+                .iter()
+                .map(|element| String::from(*element))
+                .collect::<Vec<String>>();
+
+            assert_eq!(
+                does_relationship_apply(&relationship, architecture, &build_profiles),
+                result,
+                "{input} on {architecture} with {build_profiles:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn match_architecture_wildcards() {
+        let vectors: Vec<(&str, &str, bool)> = Vec::from([
+            ("amd64", "amd64", true),
+            ("amd64", "any", true),
+            ("amd64", "linux-any", true),
+            ("armhf", "linux-any", true),
+            ("arm64", "linux-any", true),
+            ("amd64", "any-amd64", true),
+            ("armhf", "any-amd64", false),
+            ("arm64", "any-amd64", false),
+            ("x32", "any-amd64", true),
+            ("armhf", "any-arm", true),
+            ("armel", "any-arm", true),
+            ("arm64", "any-arm", false),
+            ("amd64", "any-arm", false),
+            ("arm64", "any-arm64", true),
+            ("armhf", "eabihf-gnu-linux-arm", true),
+            ("armhf", "armel", false),
+            ("amd64", "hurd-any", false),
+            ("amd64", "kfreebsd-amd64", false),
+        ]);
+
+        for (architecture, wildcard, result) in vectors {
+            assert_eq!(
+                does_architecture_match_wildcard(architecture, wildcard),
+                result,
+                "{architecture} ~ {wildcard}"
+            );
+        }
+    }
+}
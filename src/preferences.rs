@@ -0,0 +1,391 @@
+use crate::functions::*;
+use crate::indices::*;
+use crate::package::*;
+
+#[derive(Debug, Default, Clone)]
+pub struct Preference {
+    pub packages: Vec<String>,
+    pub pin: String,
+    pub pin_priority: i32,
+}
+
+pub fn parse_preferences_file(
+    preferences_file: &str,
+    message_config: &MessageConfig,
+) -> Result<Vec<Preference>, ()> {
+    let mut preferences: Vec<Preference> = Vec::new();
+
+    match std::fs::read_to_string(preferences_file) {
+        Ok(result) => {
+            let preferences_entries: Vec<String> = result
+                .trim()
+                .split("\n\n")
+                .map(|element| String::from(element))
+                .collect::<Vec<String>>();
+
+            for entry in preferences_entries {
+                let mut entries_packages: Vec<String> = Vec::new();
+                let mut entries_pin: String = String::new();
+                let mut entries_pin_priority: String = String::new();
+
+                for line in entry.lines() {
+                    match &line as &str {
+                        _ if line.starts_with("#") => {}
+                        _ if line.starts_with("Package: ") => {
+                            entries_packages = line
+                                .replacen("Package: ", "", 1)
+                                .split_whitespace()
+                                .map(|element| String::from(element))
+                                .collect::<Vec<String>>();
+                        }
+                        _ if line.starts_with("Pin: ") => {
+                            entries_pin = String::from(line.replacen("Pin: ", "", 1).trim());
+                        }
+                        _ if line.starts_with("Pin-Priority: ") => {
+                            entries_pin_priority =
+                                String::from(line.replacen("Pin-Priority: ", "", 1).trim());
+                        }
+                        _ => {}
+                    };
+                }
+
+                // a record that only consists of comments and explanations is ignored, like apt
+                // does.
+                if entries_packages.len() == 0
+                    && entries_pin.is_empty() == true
+                    && entries_pin_priority.is_empty() == true
+                {
+                    continue;
+                };
+
+                //////////////////////////////////
+
+                if entries_packages.len() == 0 {
+                    print_message(
+                        "error",
+                        &format!("no package(s) were provided in preferences file: \"{preferences_file}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                };
+
+                for package in &entries_packages {
+                    if package.starts_with("/") == true {
+                        print_message(
+                            "error",
+                            &format!("regular expressions are not supported: \"{package}\""),
+                            &message_config,
+                        );
+                        return Err(());
+                    };
+                }
+
+                //////////////////////////////////
+
+                if entries_pin.starts_with("release ") == false
+                    && entries_pin.starts_with("origin ") == false
+                    && entries_pin.starts_with("version ") == false
+                {
+                    print_message(
+                        "error",
+                        &format!("invalid pin: \"{entries_pin}\""),
+                        &message_config,
+                    );
+                    return Err(());
+                };
+
+                //////////////////////////////////
+
+                let pin_priority: i32;
+
+                match entries_pin_priority.parse::<i32>() {
+                    Ok(result) => pin_priority = result,
+                    Err(..) => {
+                        print_message(
+                            "error",
+                            &format!("invalid pin priority: \"{entries_pin_priority}\""),
+                            &message_config,
+                        );
+                        return Err(());
+                    }
+                };
+
+                //////////////////////////////////
+
+                preferences.push(Preference {
+                    packages: entries_packages,
+                    pin: entries_pin,
+                    pin_priority: pin_priority,
+                });
+            }
+        }
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read preferences file: \"{preferences_file}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    return Ok(preferences);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn does_pin_match(package: &Package, release: &Release, pin: &str) -> bool {
+    match pin {
+        _ if pin.starts_with("version ") => {
+            return does_value_match_pattern(
+                &package.version,
+                pin.replacen("version ", "", 1).trim(),
+            );
+        }
+        _ if pin.starts_with("origin ") => {
+            let host: &str = package.origin_uri_path.split('/').next().unwrap_or("");
+
+            return does_value_match_pattern(
+                host,
+                pin.replacen("origin ", "", 1).trim().trim_matches('"'),
+            );
+        }
+        _ if pin.starts_with("release ") => {
+            for condition in pin.replacen("release ", "", 1).split(',') {
+                let condition: &str = condition.trim();
+
+                // like apt, a condition without a key is the version of the release.
+                let (key, pattern) = condition.split_once('=').unwrap_or(("v", condition));
+
+                let value: &str = match key.trim() {
+                    "a" | "archive" => &release.suite,
+                    "n" | "codename" => &release.code_name,
+                    "v" | "version" => &release.version,
+                    "o" | "origin" => &release.origin,
+                    "l" | "label" => &release.label,
+                    "c" | "component" => &package.origin_component,
+                    "b" | "architecture" => &package.origin_architecture,
+                    _ => return false,
+                };
+
+                if does_value_match_pattern(value, pattern.trim().trim_matches('"')) == false {
+                    return false;
+                };
+            }
+
+            return true;
+        }
+        _ => return false,
+    };
+}
+
+pub fn get_default_pin_priority(release: &Release) -> i32 {
+    if release.not_automatic == true {
+        if release.but_automatic_upgrades == true {
+            return 100;
+        };
+
+        return 1;
+    };
+
+    return 500;
+}
+
+pub fn get_pin_priority(
+    package: &Package,
+    release: &Release,
+    preferences: &Vec<Preference>,
+) -> i32 {
+    // the first matching record that names the package wins over the first matching general one.
+    for is_general in [false, true] {
+        for preference in preferences {
+            if (preference.packages == ["*"]) != is_general {
+                continue;
+            };
+
+            if preference.packages.iter().any(|pattern| {
+                return does_value_match_pattern(&package.name, pattern) == true
+                    || does_value_match_pattern(
+                        &format!("{}:{}", package.name, package.architecture),
+                        pattern,
+                    ) == true;
+            }) == false
+            {
+                continue;
+            };
+
+            if does_pin_match(package, release, &preference.pin) == true {
+                return preference.pin_priority;
+            };
+        }
+    }
+
+    return get_default_pin_priority(release);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// the percentage of machines a phased update is rolled out to, a package without the field isn't
+// phased.
+pub fn get_phased_update_percentage(package: &Package) -> u32 {
+    for (name, value) in &package.other_fields {
+        if name.eq_ignore_ascii_case("Phased-Update-Percentage") == true {
+            match value.trim().parse::<u32>() {
+                Ok(result) if result <= 100 => return result,
+                _ => return 100,
+            };
+        };
+    }
+
+    return 100;
+}
+
+// the number between 0 and 100 that apt draws for a seed, i.e. std::uniform_int_distribution<>(0,
+// 100) of std::minstd_rand seeded with std::seed_seq as implemented by libstdc++.
+fn get_phased_update_roll(seed: &str) -> u32 {
+    let values: Vec<u32> = seed
+        .bytes()
+        .map(|byte| byte as i8 as u32)
+        .collect::<Vec<u32>>();
+
+    //////////////////////////////////////
+
+    // std::seed_seq::generate() for the 4 words that std::minstd_rand asks for.
+    let n: usize = 4;
+    let s: usize = values.len();
+    let t: usize = 1;
+    let p: usize = (n - t) / 2;
+    let q: usize = p + t;
+    let m: usize = std::cmp::max(s + 1, n);
+
+    let mut words: [u32; 4] = [0x8b8b8b8b; 4];
+    let mix = |x: u32| -> u32 { x ^ (x >> 27) };
+
+    for k in 0..m {
+        let r1: u32 =
+            mix(words[k % n] ^ words[(k + p) % n] ^ words[(k + n - 1) % n]).wrapping_mul(1664525);
+        let r2: u32 = r1.wrapping_add(match k {
+            0 => s as u32,
+            _ if k <= s => ((k % n) as u32).wrapping_add(values[k - 1]),
+            _ => (k % n) as u32,
+        });
+
+        words[(k + p) % n] = words[(k + p) % n].wrapping_add(r1);
+        words[(k + q) % n] = words[(k + q) % n].wrapping_add(r2);
+        words[k % n] = r2;
+    }
+
+    for k in m..m + n {
+        let r3: u32 = mix(words[k % n]
+            .wrapping_add(words[(k + p) % n])
+            .wrapping_add(words[(k + n - 1) % n]))
+        .wrapping_mul(1566083941);
+        let r4: u32 = r3.wrapping_sub((k % n) as u32);
+
+        words[(k + p) % n] ^= r3;
+        words[(k + q) % n] ^= r4;
+        words[k % n] = r4;
+    }
+
+    //////////////////////////////////////
+
+    // std::minstd_rand is seeded with the last word, a state of 0 is replaced with 1.
+    let modulus: u64 = 2147483647;
+    let mut state: u64 = words[3] as u64 % modulus;
+
+    if state == 0 {
+        state = 1;
+    };
+
+    // std::uniform_int_distribution downscales by rejecting the values past the largest multiple of
+    // 101 in the range of the generator (1 to 2147483646).
+    let scaling: u64 = (modulus - 2) / 101;
+    let past: u64 = 101 * scaling;
+
+    loop {
+        state = state * 48271 % modulus;
+
+        if state - 1 < past {
+            return ((state - 1) / scaling) as u32;
+        };
+    }
+}
+
+// like apt, a phased update that the machine isn't part of yet is capped at the pin priority 1, so
+// the version it updates is selected instead. 'phased_updates' is "always", "never", or the machine
+// id to draw with.
+pub fn is_phased_update_excluded(package: &Package, phased_updates: &str) -> bool {
+    let percentage: u32 = get_phased_update_percentage(package);
+
+    if percentage == 100 {
+        return false;
+    };
+
+    match phased_updates {
+        "always" => return false,
+        "never" => return true,
+        machine_id => {
+            return get_phased_update_roll(&format!(
+                "{}-{}-{machine_id}",
+                package.source, package.source_version
+            )) > percentage;
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the expected rolls were computed with the same distribution and engine in C++ built by g++.
+    #[test]
+    fn roll_like_libstdcxx() {
+        let vectors: Vec<(&str, u32)> = Vec::from([
+            ("bash-5.1-6ubuntu1.1-0123456789abcdef0123456789abcdef", 84),
+            ("a", 94),
+            ("", 63),
+            ("systemd-249.11-0ubuntu3.12-deadbeef", 68),
+            ("xx-1.0-é", 13),
+        ]);
+
+        for (seed, roll) in vectors {
+            assert_eq!(get_phased_update_roll(seed), roll, "{seed:?}");
+        }
+    }
+
+    #[test]
+    fn exclude_phased_updates() {
+        let mut package: Package = Package {
+            source: String::from("bash"),
+            source_version: String::from("5.1-6ubuntu1.1"),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            is_phased_update_excluded(&package, "0123456789abcdef0123456789abcdef"),
+            false
+        );
+
+        package.other_fields.push((
+            std::rc::Rc::from("Phased-Update-Percentage"),
+            String::from("84"),
+        ));
+
+        assert_eq!(get_phased_update_percentage(&package), 84);
+        assert_eq!(
+            is_phased_update_excluded(&package, "0123456789abcdef0123456789abcdef"),
+            false
+        );
+        assert_eq!(is_phased_update_excluded(&package, "never"), true);
+
+        package.other_fields[0].1 = String::from("83");
+
+        assert_eq!(
+            is_phased_update_excluded(&package, "0123456789abcdef0123456789abcdef"),
+            true
+        );
+        assert_eq!(is_phased_update_excluded(&package, "always"), false);
+    }
+}
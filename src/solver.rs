@@ -0,0 +1,749 @@
+use crate::algorithms::*;
+use crate::database::*;
+use crate::functions::*;
+use crate::package::*;
+
+use std::collections::HashMap;
+
+// literals are the index of a variable plus one, negated when the variable has to be false.
+type Literal = i32;
+
+#[derive(Debug, Default, Clone)]
+struct Clause {
+    literals: Vec<Literal>,
+    description: String,
+    sources: Vec<usize>,
+}
+
+#[derive(Debug, Default, Clone)]
+struct DecisionClause {
+    trigger: Literal,
+    choices: Vec<Literal>,
+}
+
+#[derive(Debug, Default)]
+struct Solver {
+    clauses: Vec<Clause>,
+    watches: Vec<Vec<usize>>,
+    assignments: Vec<Option<bool>>,
+    levels: Vec<usize>,
+    reasons: Vec<Option<usize>>,
+    trail: Vec<Literal>,
+    trail_limits: Vec<usize>,
+    propagation_index: usize,
+    decision_clauses: Vec<DecisionClause>,
+    decision_counter: u64,
+    conflict_counter: u64,
+}
+
+fn variable_of(literal: Literal) -> usize {
+    return (literal.unsigned_abs() - 1) as usize;
+}
+
+fn watch_index_of(literal: Literal) -> usize {
+    return variable_of(literal) * 2 + (literal < 0) as usize;
+}
+
+impl Solver {
+    fn new(amount_of_variables: usize) -> Self {
+        Self {
+            watches: vec![Vec::new(); amount_of_variables * 2],
+            assignments: vec![None; amount_of_variables],
+            levels: vec![0; amount_of_variables],
+            reasons: vec![None; amount_of_variables],
+            ..Default::default()
+        }
+    }
+
+    fn value_of(&self, literal: Literal) -> Option<bool> {
+        match self.assignments[variable_of(literal)] {
+            Some(value) => return Some(value == (literal > 0)),
+            None => return None,
+        };
+    }
+
+    fn current_level(&self) -> usize {
+        return self.trail_limits.len();
+    }
+
+    fn enqueue(&mut self, literal: Literal, reason: Option<usize>) {
+        let variable: usize = variable_of(literal);
+
+        self.assignments[variable] = Some(literal > 0);
+        self.levels[variable] = self.current_level();
+        self.reasons[variable] = reason;
+        self.trail.push(literal);
+    }
+
+    fn add_clause(&mut self, literals: Vec<Literal>, description: String, sources: Vec<usize>) {
+        let mut unique_literals: Vec<Literal> = Vec::new();
+
+        for literal in literals {
+            if unique_literals.contains(&literal) == false {
+                unique_literals.push(literal);
+            };
+        }
+
+        let clause_index: usize = self.clauses.len();
+
+        if unique_literals.len() >= 2 {
+            self.watches[watch_index_of(unique_literals[0])].push(clause_index);
+            self.watches[watch_index_of(unique_literals[1])].push(clause_index);
+        };
+
+        self.clauses.push(Clause {
+            literals: unique_literals,
+            description: description,
+            sources: sources,
+        });
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        while self.propagation_index < self.trail.len() {
+            let false_literal: Literal = -self.trail[self.propagation_index];
+
+            self.propagation_index += 1;
+
+            let watch_list: Vec<usize> =
+                std::mem::take(&mut self.watches[watch_index_of(false_literal)]);
+
+            let mut kept_watches: Vec<usize> = Vec::new();
+            let mut conflict: Option<usize> = None;
+
+            for clause_index in watch_list {
+                if conflict.is_some() == true {
+                    kept_watches.push(clause_index);
+                    continue;
+                };
+
+                // the false literal is always moved to the second watched position.
+                if self.clauses[clause_index].literals[0] == false_literal {
+                    self.clauses[clause_index].literals.swap(0, 1);
+                };
+
+                let first_literal: Literal = self.clauses[clause_index].literals[0];
+
+                if self.value_of(first_literal) == Some(true) {
+                    kept_watches.push(clause_index);
+                    continue;
+                };
+
+                let mut was_new_watch_found: bool = false;
+
+                for index in 2..self.clauses[clause_index].literals.len() {
+                    let literal: Literal = self.clauses[clause_index].literals[index];
+
+                    if self.value_of(literal) != Some(false) {
+                        self.clauses[clause_index].literals.swap(1, index);
+                        self.watches[watch_index_of(literal)].push(clause_index);
+                        was_new_watch_found = true;
+                        break;
+                    };
+                }
+
+                if was_new_watch_found == true {
+                    continue;
+                };
+
+                kept_watches.push(clause_index);
+
+                if self.value_of(first_literal) == Some(false) {
+                    conflict = Some(clause_index);
+                } else {
+                    self.enqueue(first_literal, Some(clause_index));
+                };
+            }
+
+            self.watches[watch_index_of(false_literal)] = kept_watches;
+
+            if conflict.is_some() == true {
+                return conflict;
+            };
+        }
+
+        return None;
+    }
+
+    fn analyze(&self, conflict: usize) -> (Vec<Literal>, usize, Vec<usize>) {
+        // first unique implication point learning.
+        let mut seen: Vec<bool> = vec![false; self.assignments.len()];
+        let mut learned_clause: Vec<Literal> = Vec::from([0]);
+        let mut sources: Vec<usize> = Vec::new();
+        let mut amount_at_current_level: usize = 0;
+        let mut implied_literal: Option<Literal> = None;
+        let mut clause_index: usize = conflict;
+        let mut trail_index: usize = self.trail.len();
+
+        loop {
+            sources.push(clause_index);
+
+            for literal in &self.clauses[clause_index].literals {
+                if Some(*literal) == implied_literal {
+                    continue;
+                };
+
+                let variable: usize = variable_of(*literal);
+
+                if seen[variable] == false && self.levels[variable] > 0 {
+                    seen[variable] = true;
+
+                    if self.levels[variable] == self.current_level() {
+                        amount_at_current_level += 1;
+                    } else {
+                        learned_clause.push(*literal);
+                    };
+                };
+            }
+
+            loop {
+                trail_index -= 1;
+
+                if seen[variable_of(self.trail[trail_index])] == true {
+                    break;
+                };
+            }
+
+            let literal: Literal = self.trail[trail_index];
+
+            seen[variable_of(literal)] = false;
+            amount_at_current_level -= 1;
+
+            if amount_at_current_level == 0 {
+                learned_clause[0] = -literal;
+                break;
+            };
+
+            clause_index = self.reasons[variable_of(literal)].unwrap();
+            implied_literal = Some(literal);
+        }
+
+        let mut backjump_level: usize = 0;
+
+        // the literal with the highest level is watched next to the asserting literal.
+        for index in 1..learned_clause.len() {
+            let level: usize = self.levels[variable_of(learned_clause[index])];
+
+            if level > backjump_level {
+                backjump_level = level;
+                learned_clause.swap(1, index);
+            };
+        }
+
+        return (learned_clause, backjump_level, sources);
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.current_level() <= level {
+            return;
+        };
+
+        let trail_length: usize = self.trail_limits[level];
+
+        for literal in self.trail.drain(trail_length..) {
+            let variable: usize = variable_of(literal);
+
+            self.assignments[variable] = None;
+            self.reasons[variable] = None;
+        }
+
+        self.trail_limits.truncate(level);
+        self.propagation_index = self.trail.len();
+    }
+
+    fn decide(&self) -> Option<Literal> {
+        // decisions follow the order the relations were encountered in, and always pick the first
+        // alternative that is still open, so the result is close to what the relations ask for.
+        for decision_clause in &self.decision_clauses {
+            if self.value_of(decision_clause.trigger) != Some(false) {
+                continue;
+            };
+
+            if decision_clause
+                .choices
+                .iter()
+                .any(|choice| self.value_of(*choice) == Some(true))
+                == true
+            {
+                continue;
+            };
+
+            for choice in &decision_clause.choices {
+                if self.value_of(*choice).is_none() == true {
+                    return Some(*choice);
+                };
+            }
+        }
+
+        return None;
+    }
+
+    fn explain(&self, conflict: usize) -> Vec<String> {
+        let mut visited: Vec<bool> = vec![false; self.clauses.len()];
+        let mut clauses_to_visit: Vec<usize> = Vec::from([conflict]);
+        let mut core: Vec<usize> = Vec::new();
+
+        while let Some(clause_index) = clauses_to_visit.pop() {
+            if visited[clause_index] == true {
+                continue;
+            };
+
+            visited[clause_index] = true;
+
+            if self.clauses[clause_index].description.is_empty() == false {
+                core.push(clause_index);
+            };
+
+            clauses_to_visit.extend(self.clauses[clause_index].sources.iter());
+
+            for literal in &self.clauses[clause_index].literals {
+                match self.reasons[variable_of(*literal)] {
+                    Some(reason) => clauses_to_visit.push(reason),
+                    None => {}
+                };
+            }
+        }
+
+        core.sort_unstable();
+
+        return core
+            .iter()
+            .map(|clause_index| self.clauses[*clause_index].description.clone())
+            .collect::<Vec<String>>();
+    }
+
+    fn solve(&mut self) -> Result<(), Vec<String>> {
+        for clause_index in 0..self.clauses.len() {
+            if self.clauses[clause_index].literals.len() == 1 {
+                let literal: Literal = self.clauses[clause_index].literals[0];
+
+                match self.value_of(literal) {
+                    Some(true) => {}
+                    Some(false) => return Err(self.explain(clause_index)),
+                    None => self.enqueue(literal, Some(clause_index)),
+                };
+            };
+        }
+
+        loop {
+            match self.propagate() {
+                Some(conflict) => {
+                    self.conflict_counter += 1;
+
+                    if self.current_level() == 0 {
+                        return Err(self.explain(conflict));
+                    };
+
+                    let (learned_clause, backjump_level, sources) = self.analyze(conflict);
+
+                    self.backtrack(backjump_level);
+
+                    let asserting_literal: Literal = learned_clause[0];
+                    let clause_index: usize = self.clauses.len();
+
+                    self.add_clause(learned_clause, String::new(), sources);
+                    self.enqueue(asserting_literal, Some(clause_index));
+                }
+                None => match self.decide() {
+                    Some(literal) => {
+                        self.decision_counter += 1;
+                        self.trail_limits.push(self.trail.len());
+                        self.enqueue(literal, None);
+                    }
+                    None => return Ok(()),
+                },
+            };
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+fn describe_package(package: &Package, architecture_config: &ArchitectureConfig) -> String {
+    return format!(
+        "\"{}\" ({})",
+        format_package_name(package, architecture_config),
+        package.version
+    );
+}
+
+pub fn solve_dependencies(
+    package_database: &PackageDatabase,
+    input_package_set: &Vec<Package>,
+    consider_recommends: &bool,
+    packages_to_prohibit: &Vec<String>,
+    provider_preferences: &HashMap<String, String>,
+    architecture_config: &ArchitectureConfig,
+    message_config: &MessageConfig,
+) -> Result<Vec<Package>, ()> {
+    // only the packages reachable from the initial package set become variables.
+    let mut variables: Vec<Package> = Vec::new();
+    let mut variable_indices: HashMap<(String, String, String), usize> = HashMap::new();
+
+    let mut find_or_add_variable = |package: &Package, variables: &mut Vec<Package>| -> usize {
+        let key: (String, String, String) = (
+            package.name.clone(),
+            package.version.clone(),
+            package.architecture.clone(),
+        );
+
+        match variable_indices.get(&key) {
+            Some(index) => return *index,
+            None => {
+                variables.push(package.clone());
+                variable_indices.insert(key, variables.len() - 1);
+                return variables.len() - 1;
+            }
+        };
+    };
+
+    let mut hard_clauses: Vec<(Vec<Literal>, String)> = Vec::new();
+    let mut decision_clauses: Vec<DecisionClause> = Vec::new();
+
+    for package in input_package_set {
+        let index: usize = find_or_add_variable(package, &mut variables);
+
+        hard_clauses.push((
+            Vec::from([index as Literal + 1]),
+            format!(
+                "{} is part of the initial package set",
+                describe_package(package, &architecture_config)
+            ),
+        ));
+    }
+
+    let mut index_to_expand: usize = 0;
+
+    while index_to_expand < variables.len() {
+        let package: Package = variables[index_to_expand].clone();
+        let trigger: Literal = -(index_to_expand as Literal + 1);
+
+        index_to_expand += 1;
+
+        let mut relationships_to_satisfy: Vec<(&str, Vec<Relationship>)> = Vec::new();
+
+        for dependency in &package.pre_depends {
+            relationships_to_satisfy.push(("pre-depends on", dependency.clone()));
+        }
+
+        for dependency in &package.depends {
+            relationships_to_satisfy.push(("depends on", dependency.clone()));
+        }
+
+        if *consider_recommends == true {
+            for dependency in &package.recommends {
+                relationships_to_satisfy.push(("recommends", dependency.clone()));
+            }
+        };
+
+        for (kind, dependency) in relationships_to_satisfy {
+            let mut choices: Vec<Literal> = Vec::new();
+
+            for alternative in &dependency {
+                let mut candidates: Vec<Package> = select_candidates(
+                    &package_database,
+                    &alternative,
+                    &package.architecture,
+                    &architecture_config,
+                );
+
+                // the order of the providers decides which one is tried first, a relation that is
+                // already satisfied by a selected provider isn't decided on at all.
+                candidates.extend(select_providers(
+                    &package_database,
+                    &alternative,
+                    &package.architecture,
+                    &[],
+                    &provider_preferences,
+                    &architecture_config,
+                ));
+
+                for candidate in &candidates {
+                    let choice: Literal =
+                        find_or_add_variable(candidate, &mut variables) as Literal + 1;
+
+                    if choices.contains(&choice) == false {
+                        choices.push(choice);
+                    };
+                }
+            }
+
+            decision_clauses.push(DecisionClause {
+                trigger: trigger,
+                choices: choices.clone(),
+            });
+
+            // recommendations are only followed as long as they can be installed.
+            if kind == "recommends" {
+                continue;
+            };
+
+            let mut literals: Vec<Literal> = Vec::from([trigger]);
+
+            literals.extend(choices);
+
+            hard_clauses.push((
+                literals,
+                format!(
+                    "{} {kind}: \"{}\"",
+                    describe_package(&package, &architecture_config),
+                    format_relationship_alternatives(&dependency)
+                ),
+            ));
+        }
+    }
+
+    let variables: Vec<Package> = variables;
+
+    //////////////////////////////////////////////
+
+    let mut versions_of_package: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut variables_by_name: HashMap<String, Vec<usize>> = HashMap::new();
+
+    for (index, package) in variables.iter().enumerate() {
+        versions_of_package
+            .entry(package.name.clone())
+            .or_default()
+            .push(index);
+
+        variables_by_name
+            .entry(package.name.clone())
+            .or_default()
+            .push(index);
+
+        for provided in package.provides.iter().flatten() {
+            variables_by_name
+                .entry(String::from(&*provided.name))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut package_names: Vec<&String> = versions_of_package.keys().collect();
+
+    package_names.sort_unstable();
+
+    // only one version of a package can be installed per architecture, and only 'Multi-Arch: same'
+    // packages for several architectures.
+    for name in package_names {
+        let indices: &Vec<usize> = &versions_of_package[name];
+
+        for (position, first) in indices.iter().enumerate() {
+            for second in &indices[position + 1..] {
+                match find_conflict(
+                    &variables[*first],
+                    &variables[*second],
+                    &architecture_config,
+                ) {
+                    Some(explanation) => {
+                        hard_clauses.push((
+                            Vec::from([-(*first as Literal + 1), -(*second as Literal + 1)]),
+                            explanation,
+                        ));
+                    }
+                    None => {}
+                };
+            }
+        }
+    }
+
+    for (index, package) in variables.iter().enumerate() {
+        for relationship in package
+            .conflicts
+            .iter()
+            .chain(package.breaks.iter())
+            .flatten()
+        {
+            match variables_by_name.get(&*relationship.name) {
+                Some(result) => {
+                    for other_index in result {
+                        match find_conflict(package, &variables[*other_index], &architecture_config)
+                        {
+                            Some(explanation) => {
+                                hard_clauses.push((
+                                    Vec::from([
+                                        -(index as Literal + 1),
+                                        -(*other_index as Literal + 1),
+                                    ]),
+                                    explanation,
+                                ));
+                            }
+                            None => {}
+                        };
+                    }
+                }
+                None => {}
+            };
+        }
+
+        if packages_to_prohibit.contains(&package.name) == true {
+            hard_clauses.push((
+                Vec::from([-(index as Literal + 1)]),
+                format!(
+                    "{} is prohibited",
+                    describe_package(package, &architecture_config)
+                ),
+            ));
+        };
+    }
+
+    //////////////////////////////////////////////
+
+    let mut solver: Solver = Solver::new(variables.len());
+
+    for (literals, description) in hard_clauses {
+        solver.add_clause(literals, description, Vec::new());
+    }
+
+    solver.decision_clauses = decision_clauses;
+
+    print_message(
+        "debug",
+        &format!(
+            "solving: \"{}\" packages, \"{}\" clauses",
+            variables.len(),
+            solver.clauses.len()
+        ),
+        &message_config,
+    );
+
+    let result: Result<(), Vec<String>> = solver.solve();
+
+    print_message(
+        "debug",
+        &format!(
+            "decisions: \"{}\", conflicts: \"{}\"",
+            solver.decision_counter, solver.conflict_counter
+        ),
+        &message_config,
+    );
+
+    match result {
+        Ok(..) => {}
+        Err(explanations) => {
+            print_message(
+                "error",
+                "the requested package set is uninstallable, the following constraints can't be satisfied together:",
+                &message_config,
+            );
+
+            for explanation in &explanations {
+                print_message("error", &format!("    {explanation}"), &message_config);
+            }
+            return Err(());
+        }
+    };
+
+    let mut output: Vec<Package> = Vec::new();
+
+    for (index, package) in variables.iter().enumerate() {
+        if solver.assignments[index] == Some(true) {
+            output.push(package.clone());
+        };
+    }
+
+    output.sort_unstable();
+
+    if output.len() == 0 {
+        print_message("error", "failed to resolve dependencies.", &message_config);
+        return Err(());
+    };
+
+    return Ok(output);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_clauses(solver: &mut Solver, clauses: &[(&[Literal], &str)]) {
+        for (literals, description) in clauses {
+            solver.add_clause(literals.to_vec(), String::from(*description), Vec::new());
+        }
+    }
+
+    // a, b, c, and d are the variables 1 to 4.
+    #[test]
+    fn explain_conflict_only_instance() {
+        let mut solver: Solver = Solver::new(4);
+
+        add_clauses(
+            &mut solver,
+            &[
+                (&[1], "a is required"),
+                (&[2], "b is required"),
+                (&[-3, -4], "c conflicts with d"),
+                (&[-1, -2], "a conflicts with b"),
+                (&[-4, -1], "d conflicts with a"),
+            ],
+        );
+
+        assert_eq!(
+            solver.solve(),
+            Err(Vec::from([
+                String::from("a is required"),
+                String::from("b is required"),
+                String::from("a conflicts with b"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn explain_instance_with_dependencies() {
+        let mut solver: Solver = Solver::new(4);
+
+        // both alternatives of the dependency lead to a conflict with a, one of them indirectly.
+        add_clauses(
+            &mut solver,
+            &[
+                (&[1], "a is required"),
+                (&[-1, 2, 3], "a depends on b | c"),
+                (&[-2, 4], "b depends on d"),
+                (&[-4, -1], "d conflicts with a"),
+                (&[-3, -1], "c conflicts with a"),
+            ],
+        );
+
+        solver.decision_clauses.push(DecisionClause {
+            trigger: -1,
+            choices: Vec::from([2, 3]),
+        });
+
+        assert_eq!(
+            solver.solve(),
+            Err(Vec::from([
+                String::from("a is required"),
+                String::from("a depends on b | c"),
+                String::from("b depends on d"),
+                String::from("d conflicts with a"),
+                String::from("c conflicts with a"),
+            ]))
+        );
+    }
+
+    #[test]
+    fn solve_by_picking_the_second_alternative() {
+        let mut solver: Solver = Solver::new(3);
+
+        add_clauses(
+            &mut solver,
+            &[
+                (&[1], "a is required"),
+                (&[-1, 2, 3], "a depends on b | c"),
+                (&[-2, -1], "b conflicts with a"),
+            ],
+        );
+
+        solver.decision_clauses.push(DecisionClause {
+            trigger: -1,
+            choices: Vec::from([2, 3]),
+        });
+
+        assert_eq!(solver.solve(), Ok(()));
+        assert_eq!(
+            solver.assignments,
+            Vec::from([Some(true), Some(false), Some(true)])
+        );
+    }
+}
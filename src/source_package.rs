@@ -0,0 +1,567 @@
+use crate::algorithms::*;
+use crate::database::*;
+use crate::deb822::*;
+use crate::functions::*;
+use crate::package::*;
+
+use byte_unit::{Byte, Unit, UnitType};
+use cmd_lib::run_cmd;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SourceFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256_hash: String,
+    pub md5_hash: String,
+}
+
+#[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SourcePackage {
+    pub name: String,
+    pub version: String,
+    pub binaries: Vec<String>,
+    pub build_depends: String,
+    pub build_depends_arch: String,
+    pub build_depends_indep: String,
+    pub directory: String,
+    pub files: Vec<SourceFile>,
+    pub origin_suite: String,
+    pub origin_component: String,
+    pub origin_uri_scheme: String,
+    pub origin_uri_path: String,
+}
+
+impl SourcePackage {
+    pub fn new(
+        paragraph: &Paragraph,
+        origin_suite: &str,
+        origin_component: &str,
+        origin_uri_scheme: &str,
+        origin_uri_path: &str,
+    ) -> Result<Self, Deb822Error> {
+        // a '.dsc' file names the source package in the field 'Source'.
+        let name_field: &str = match paragraph.get("Package") {
+            Some(..) => "Package",
+            None => "Source",
+        };
+
+        let mut required_values: Vec<String> = Vec::new();
+
+        for field in [name_field, "Version"] {
+            match paragraph.get(field) {
+                Some(value) if value.is_empty() == false => {
+                    required_values.push(String::from(value));
+                }
+                _ => {
+                    return Err(paragraph.error(field, &format!("missing field: \"{field}\"")));
+                }
+            };
+        }
+
+        let name: String = required_values[0].clone();
+        let version: String = required_values[1].clone();
+
+        //////////////////////////////////////
+
+        let mut files: Vec<SourceFile> = Vec::new();
+
+        for field in ["Checksums-Sha256", "Files"] {
+            for (line_number, line) in paragraph.get_lines(field) {
+                let split_line: Vec<&str> = line.split_whitespace().collect::<Vec<&str>>();
+
+                if split_line.len() != 3 {
+                    return Err(Deb822Error {
+                        line_number: line_number,
+                        message: format!("invalid line of field: \"{field}\": \"{}\"", line.trim()),
+                    });
+                };
+
+                let file_name: String = String::from(split_line[2]);
+
+                let file_size: u64 = match split_line[1].parse::<u64>() {
+                    Ok(result) => result,
+                    Err(..) => {
+                        return Err(Deb822Error {
+                            line_number: line_number,
+                            message: format!(
+                                "invalid size of file: \"{file_name}\": \"{}\"",
+                                split_line[1]
+                            ),
+                        });
+                    }
+                };
+
+                let index: usize;
+
+                match files.iter().position(|file| file.name == file_name) {
+                    Some(result) => index = result,
+                    None => {
+                        files.push(SourceFile {
+                            name: file_name,
+                            size: file_size,
+                            ..Default::default()
+                        });
+                        index = files.len() - 1;
+                    }
+                };
+
+                match field {
+                    "Checksums-Sha256" => files[index].sha256_hash = String::from(split_line[0]),
+                    _ => files[index].md5_hash = String::from(split_line[0]),
+                };
+            }
+        }
+
+        let binaries: Vec<String> = paragraph
+            .get_folded("Binary")
+            .unwrap_or_default()
+            .split(',')
+            .map(|binary| String::from(binary.trim()))
+            .filter(|binary| binary.is_empty() == false)
+            .collect::<Vec<String>>();
+
+        //////////////////////////////////////
+
+        return Ok(Self {
+            name: name,
+            version: version,
+            binaries: binaries,
+            build_depends: paragraph.get_folded("Build-Depends").unwrap_or_default(),
+            build_depends_arch: paragraph
+                .get_folded("Build-Depends-Arch")
+                .unwrap_or_default(),
+            build_depends_indep: paragraph
+                .get_folded("Build-Depends-Indep")
+                .unwrap_or_default(),
+            directory: String::from(paragraph.get("Directory").unwrap_or_default()),
+            files: files,
+            origin_suite: String::from(origin_suite),
+            origin_component: String::from(origin_component),
+            origin_uri_scheme: String::from(origin_uri_scheme),
+            origin_uri_path: String::from(origin_uri_path),
+        });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn find_source_packages(
+    source_package_database: &HashMap<String, Vec<SourcePackage>>,
+    target_package_set: &Vec<Package>,
+    message_config: &MessageConfig,
+) -> Result<Vec<SourcePackage>, ()> {
+    let mut source_packages: Vec<SourcePackage> = Vec::new();
+    let mut missing_source_packages: Vec<String> = Vec::new();
+
+    for package in target_package_set {
+        if source_packages.iter().any(|source_package| {
+            source_package.name == package.source
+                && source_package.version == package.source_version
+        }) == true
+        {
+            continue;
+        };
+
+        let source_package: Option<&SourcePackage>;
+
+        match source_package_database.get(&package.source) {
+            Some(result) => {
+                source_package = result
+                    .iter()
+                    .find(|source_package| source_package.version == package.source_version);
+            }
+            None => source_package = None,
+        };
+
+        match source_package {
+            Some(result) => {
+                print_message(
+                    "debug",
+                    &format!(
+                        "package: \"{}\" was built from source package: \"{}\" ({})",
+                        package.name, result.name, result.version
+                    ),
+                    &message_config,
+                );
+
+                source_packages.push(result.clone());
+            }
+            None => {
+                let missing_source_package: String =
+                    format!("{} ({})", package.source, package.source_version);
+
+                if missing_source_packages.contains(&missing_source_package) == false {
+                    missing_source_packages.push(missing_source_package);
+                };
+            }
+        };
+    }
+
+    if missing_source_packages.len() != 0 {
+        for source_package in &missing_source_packages {
+            print_message(
+                "error",
+                &format!("failed to find source package: \"{source_package}\""),
+                &message_config,
+            );
+        }
+        return Err(());
+    };
+
+    source_packages.sort_unstable();
+
+    return Ok(source_packages);
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn download_source_packages(
+    source_packages: &Vec<SourcePackage>,
+    output_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    let counter_spacing: u16;
+
+    match source_packages.len() {
+        length if length < 10 => {
+            counter_spacing = 6;
+        }
+        length if length < 100 => {
+            counter_spacing = 8;
+        }
+        length if length < 1000 => {
+            counter_spacing = 10;
+        }
+        length if length < 10000 => {
+            counter_spacing = 12;
+        }
+        length if length < 100000 => {
+            counter_spacing = 14;
+        }
+        _ => {
+            print_message("error", "invalid size.", &message_config);
+
+            return Err(());
+        }
+    };
+
+    let mut name_length: u16 = 0;
+    let mut version_length: u16 = 0;
+
+    for source_package in source_packages {
+        if source_package.name.len() as u16 > name_length {
+            name_length = source_package.name.len() as u16;
+        };
+
+        if source_package.version.len() as u16 > version_length {
+            version_length = source_package.version.len() as u16;
+        };
+    }
+
+    let mut counter: u64 = 0;
+
+    for source_package in source_packages {
+        counter += 1;
+
+        println!(
+            "{} {}{} {}/{} {} {} {}",
+            space_and_truncate_string(
+                &format!("({counter}/{}):", source_packages.len()),
+                counter_spacing
+            ),
+            source_package.origin_uri_scheme,
+            source_package.origin_uri_path,
+            source_package.origin_suite,
+            source_package.origin_component,
+            space_and_truncate_string(&source_package.name, name_length),
+            space_and_truncate_string(&source_package.version, version_length),
+            format!(
+                "{:.2}",
+                &Byte::from_u64_with_unit(
+                    source_package
+                        .files
+                        .iter()
+                        .map(|file| file.size)
+                        .sum::<u64>(),
+                    Unit::B
+                )
+                .unwrap()
+                .get_appropriate_unit(UnitType::Binary),
+            ),
+        );
+
+        for file in &source_package.files {
+            if download_file(
+                &format!(
+                    "{}{}/{}/{}",
+                    source_package.origin_uri_scheme,
+                    source_package.origin_uri_path,
+                    source_package.directory,
+                    file.name,
+                ),
+                &output_directory,
+                &message_config,
+            )
+            .is_err()
+                == true
+            {
+                return Err(());
+            };
+
+            let downloaded_file_location: String = format!("{output_directory}/{}", file.name);
+
+            if file.sha256_hash.is_empty() == false {
+                if verify_file_checksum(
+                    "sha256",
+                    &downloaded_file_location,
+                    &file.sha256_hash,
+                    &file.size,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    return Err(());
+                };
+            } else if file.md5_hash.is_empty() == false {
+                print_message(
+                    "warning",
+                    "falling back to using md5 checksum.",
+                    &message_config,
+                );
+
+                if verify_file_checksum(
+                    "md5",
+                    &downloaded_file_location,
+                    &file.md5_hash,
+                    &file.size,
+                    &message_config,
+                )
+                .is_err()
+                    == true
+                {
+                    return Err(());
+                };
+            } else {
+                print_message(
+                    "error",
+                    &format!(
+                        "failed to find any checksums for file: \"{downloaded_file_location}\""
+                    ),
+                    &message_config,
+                );
+                return Err(());
+            };
+        }
+    }
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn bundle_source_packages(
+    input_directory: &str,
+    output_directory: &str,
+    message_config: &MessageConfig,
+) -> Result<(), ()> {
+    print_message(
+        "debug",
+        &format!("moving source packages to: \"{output_directory}\""),
+        &message_config,
+    );
+
+    // the workspace might be on a different file system than the output, so the directory can not
+    // simply be renamed.
+    if run_cmd!(mv "$input_directory" "$output_directory" 2> /dev/stdout).is_err() == true {
+        print_message(
+            "error",
+            &format!("failed to move source packages to: \"{output_directory}\""),
+            &message_config,
+        );
+        return Err(());
+    };
+
+    return Ok(());
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn read_source_package_file(
+    file_location: &str,
+    message_config: &MessageConfig,
+) -> Result<SourcePackage, ()> {
+    let mut contents: String;
+
+    match std::fs::read_to_string(file_location) {
+        Ok(result) => contents = result,
+        Err(..) => {
+            print_message(
+                "error",
+                &format!("failed to read file: \"{file_location}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+
+    // '.dsc' files are usually clearsigned, the signature is not verified but it has to be
+    // stripped so its armor headers aren't parsed as fields.
+    if contents.starts_with("-----BEGIN PGP SIGNED MESSAGE-----") == true {
+        match contents.split_once("\n\n") {
+            Some((_armor_headers, signed_contents)) => contents = String::from(signed_contents),
+            None => contents = String::new(),
+        };
+
+        match contents.split_once("\n-----BEGIN PGP SIGNATURE-----") {
+            Some((signed_contents, _signature)) => contents = String::from(signed_contents),
+            None => {}
+        };
+    };
+
+    let (paragraphs, errors) = parse_deb822_paragraphs(&contents);
+
+    match errors.first() {
+        Some(error) => {
+            print_message(
+                "error",
+                &format!(
+                    "invalid source package file: {}",
+                    format_deb822_error(file_location, error)
+                ),
+                &message_config,
+            );
+            return Err(());
+        }
+        None => {}
+    };
+
+    match paragraphs.first() {
+        Some(paragraph) => match SourcePackage::new(paragraph, "", "", "", "") {
+            Ok(result) => return Ok(result),
+            Err(error) => {
+                print_message(
+                    "error",
+                    &format!(
+                        "invalid source package file: {}",
+                        format_deb822_error(file_location, &error)
+                    ),
+                    &message_config,
+                );
+                return Err(());
+            }
+        },
+        None => {
+            print_message(
+                "error",
+                &format!("invalid source package file: \"{file_location}\""),
+                &message_config,
+            );
+            return Err(());
+        }
+    };
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub fn find_build_dependencies(
+    package_database: &PackageDatabase,
+    source_package: &SourcePackage,
+    host_architecture: &str,
+    build_profiles: &Vec<String>,
+    provider_preferences: &HashMap<String, String>,
+    architecture_config: &ArchitectureConfig,
+    message_config: &MessageConfig,
+) -> Result<Vec<Package>, ()> {
+    let mut build_dependencies: Vec<Vec<Relationship>> = Vec::new();
+
+    // both architecture dependent and independent packages are built, like 'dpkg-buildpackage'
+    // does by default.
+    for field in [
+        &source_package.build_depends,
+        &source_package.build_depends_arch,
+        &source_package.build_depends_indep,
+    ] {
+        build_dependencies.extend(apply_restrictions(
+            &parse_relationships("", field),
+            host_architecture,
+            build_profiles,
+        ));
+    }
+
+    let mut output: Vec<Package> = Vec::new();
+    let mut unsatisfied_dependencies: Vec<String> = Vec::new();
+
+    for dependency in &build_dependencies {
+        let mut was_dependency_found: bool = false;
+
+        for alternative in dependency {
+            match select_candidate(
+                &package_database,
+                &alternative,
+                host_architecture,
+                &architecture_config,
+            ) {
+                Some(result) => {
+                    output.push(result);
+                    was_dependency_found = true;
+                    break;
+                }
+                None => {}
+            };
+        }
+
+        // the build dependency might only be satisfiable through a virtual package.
+        if was_dependency_found == false {
+            for alternative in dependency {
+                let providers: Vec<Package> = select_providers(
+                    &package_database,
+                    &alternative,
+                    host_architecture,
+                    &[&output],
+                    &provider_preferences,
+                    &architecture_config,
+                );
+
+                if providers.len() != 0 {
+                    print_message(
+                        "debug",
+                        &format!(
+                            "build dependency: \"{}\" is provided by: \"{}\"",
+                            alternative.name, providers[0].name
+                        ),
+                        &message_config,
+                    );
+
+                    output.push(providers[0].clone());
+                    was_dependency_found = true;
+                    break;
+                };
+            }
+        };
+
+        if was_dependency_found == false {
+            unsatisfied_dependencies.push(format_relationship_alternatives(&dependency));
+        };
+    }
+
+    if unsatisfied_dependencies.len() != 0 {
+        for dependency in &unsatisfied_dependencies {
+            print_message(
+                "error",
+                &format!(
+                    "failed to find build dependency: \"{dependency}\" of source package: \"{}\"",
+                    source_package.name
+                ),
+                &message_config,
+            );
+        }
+        return Err(());
+    };
+
+    output.sort_unstable();
+    output.dedup();
+
+    return Ok(output);
+}